@@ -1,120 +1,18 @@
 extern crate filelib;
-//use std::collections::HashMap;
-use rustc_hash::FxHashMap;
+mod overlap;
 
+pub use crate::overlap::{count_overlaps, OverlapMode, Segment};
 pub use filelib::load_no_blanks;
 pub use filelib::parse_line_to_linecoords;
 
-fn get_points_on_line(x1: i32, y1: i32, x2: i32, y2: i32) -> Vec<(i32, i32)> {
-    return get_points_on_line_full(x1, y1, x2, y2, false);
-}
-
-/// Function to get all points a line touches
-///
-/// This exploits the fact that we are either vertical 1 by 1, or horizontal 1 by 1
-/// or diagonally 1 by 1.
-fn get_points_on_line_full(
-    x1: i32,
-    y1: i32,
-    x2: i32,
-    y2: i32,
-    include_diags: bool,
-) -> Vec<(i32, i32)> {
-    let mut points: Vec<(i32, i32)> = Vec::new();
-    if x1 != x2 && y1 != y2 && !include_diags {
-        return points;
-    }
-
-    // if we are going to the right, xsign is +1. If no horizontal, 0
-    let xsign = (x2 - x1).signum();
-    // if we aer going down, ysign is +1. If no vert, 0.
-    let ysign = (y2 - y1).signum();
-    let range = if xsign != 0 {
-        (x1 - x2).abs()
-    } else {
-        (y1 - y2).abs()
-    };
-    for i in 0..=range {
-        points.push(((x1 + xsign * i), (y1 + ysign * i)));
-    }
-
-    return points;
-}
-
-trait HydroMapMarkable {
-    fn mark_line(&mut self, line_points: Vec<(i32, i32)>);
-    fn get_points_with_gte(&self, minimum: i32) -> Vec<(i32, i32)>;
-}
-
-/// Best data structure for future problems
-///
-/// As a builtin hashmap:
-/// real    0m0.268s
-/// user    0m0.268s
-/// sys     0m0.000s
-///
-/// With FxHashMap
-/// real    0m0.179s
-/// user    0m0.179s
-/// sys     0m0.000s
-struct SparseHydroMap {
-    data: FxHashMap<(i32, i32), i32>,
-}
-
-impl HydroMapMarkable for SparseHydroMap {
-    fn mark_line(&mut self, line_points: Vec<(i32, i32)>) {
-        for pos in line_points {
-            let counter = self.data.entry(pos).or_insert(0);
-            *counter += 1;
-        }
-    }
-
-    fn get_points_with_gte(&self, minimum: i32) -> Vec<(i32, i32)> {
-        let mut result: Vec<(i32, i32)> = Vec::new();
-        for (pos, value) in self.data.iter() {
-            if value >= &minimum {
-                result.push(*pos);
-            }
-        }
-        return result;
-    }
-}
-
-/// What if I don't bother with spareness, Its only a million ints.
-///
-/// real    0m0.051s
-/// user    0m0.041s
-/// sys     0m0.010s
-struct DumbHugeArraySet {
-    data: [[i32; 1000]; 1000],
-}
-
-impl HydroMapMarkable for DumbHugeArraySet {
-    fn mark_line(&mut self, line_points: Vec<(i32, i32)>) {
-        for pos in line_points {
-            let x: usize = pos.0.try_into().unwrap();
-            let y: usize = pos.1.try_into().unwrap();
-            self.data[x][y] += 1;
-        }
-    }
-
-    fn get_points_with_gte(&self, minimum: i32) -> Vec<(i32, i32)> {
-        let mut result: Vec<(i32, i32)> = Vec::new();
-        for x in 0..1000 {
-            for y in 0..1000 {
-                if self.data[x][y] >= minimum {
-                    result.push((x.try_into().unwrap(), y.try_into().unwrap()));
-                }
-            }
-        }
-        return result;
-    }
+fn count_puzzle_overlaps(line_pairs: &[(i32, i32, i32, i32)], mode: OverlapMode) -> i32 {
+    let segments: Vec<Segment> = line_pairs.iter().map(|p| Segment::from(*p)).collect();
+    return count_overlaps(&segments, mode).try_into().unwrap();
 }
 
 /// Solution to the first puzzle.
 ///
-/// Mark all the lines in a sparse map, then grab every point in the map with a minimum of two
-/// If we needed faster then this, I would have to break out the geometry.
+/// Overlap the lines with each other rather than marking every point they cover on a map.
 /// ```
 /// let inputs = vec![
 ///                   (0, 9, 5, 9),
@@ -131,23 +29,7 @@ impl HydroMapMarkable for DumbHugeArraySet {
 /// assert_eq!(day05::puzzle_a(&inputs), 5);
 /// ```
 pub fn puzzle_a(line_pairs: &Vec<(i32, i32, i32, i32)>) -> i32 {
-    /*
-    let mut map = DumbHugeArraySet {
-        data: [[0; 1000]; 1000],
-    };
-    */
-    let mut map = SparseHydroMap {
-        data: FxHashMap::default(),
-    };
-    for pos_pair in line_pairs {
-        let x1 = pos_pair.0;
-        let y1 = pos_pair.1;
-        let x2 = pos_pair.2;
-        let y2 = pos_pair.3;
-        let all_points = get_points_on_line(x1, y1, x2, y2);
-        map.mark_line(all_points);
-    }
-    return map.get_points_with_gte(2).len().try_into().unwrap();
+    return count_puzzle_overlaps(line_pairs, OverlapMode::Axis);
 }
 
 /// Solution to the second puzzle.
@@ -169,30 +51,36 @@ pub fn puzzle_a(line_pairs: &Vec<(i32, i32, i32, i32)>) -> i32 {
 /// assert_eq!(day05::puzzle_b(&inputs), 12);
 /// ```
 pub fn puzzle_b(line_pairs: &Vec<(i32, i32, i32, i32)>) -> i32 {
-    let mut map = SparseHydroMap {
-        data: FxHashMap::default(),
-    };
-    for pos_pair in line_pairs {
-        let x1 = pos_pair.0;
-        let y1 = pos_pair.1;
-        let x2 = pos_pair.2;
-        let y2 = pos_pair.3;
-        let all_points = get_points_on_line_full(x1, y1, x2, y2, true);
-        map.mark_line(all_points);
-    }
-    return map.get_points_with_gte(2).len().try_into().unwrap();
+    return count_puzzle_overlaps(line_pairs, OverlapMode::Diagonal);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rustc_hash::FxHashMap;
+
+    fn example() -> Vec<Segment> {
+        return vec![
+            Segment::new(0, 9, 5, 9),
+            Segment::new(8, 0, 0, 8),
+            Segment::new(9, 4, 3, 4),
+            Segment::new(2, 2, 2, 1),
+            Segment::new(7, 0, 7, 4),
+            Segment::new(6, 4, 2, 0),
+            Segment::new(0, 9, 2, 9),
+            Segment::new(3, 4, 1, 4),
+            Segment::new(0, 0, 8, 8),
+            Segment::new(5, 5, 8, 2),
+        ];
+    }
 
     #[test]
     fn test_get_points_on_line() {
         let expected_horizontal = vec![(9, 7), (8, 7), (7, 7)];
         let expected_vertical = vec![(1, 1), (1, 2), (1, 3)];
-        assert_eq!(get_points_on_line(1, 1, 1, 3), expected_vertical);
-        assert_eq!(get_points_on_line(9, 7, 7, 7), expected_horizontal);
+        let points = |s: Segment| s.points().collect::<Vec<(i64, i64)>>();
+        assert_eq!(points(Segment::new(1, 1, 1, 3)), expected_vertical);
+        assert_eq!(points(Segment::new(9, 7, 7, 7)), expected_horizontal);
     }
 
     #[test]
@@ -201,61 +89,86 @@ mod tests {
         let expected_two = vec![(9, 7), (8, 8), (7, 9)];
         let expected_three = vec![(3, 3), (2, 2), (1, 1)];
         let expected_four = vec![(7, 9), (8, 8), (9, 7)];
+        let points = |s: Segment| s.points().collect::<Vec<(i64, i64)>>();
         // top left to bottom right
-        assert_eq!(get_points_on_line_full(1, 1, 3, 3, true), expected_one);
+        assert_eq!(points(Segment::new(1, 1, 3, 3)), expected_one);
         // bottom left to top right
-        assert_eq!(get_points_on_line_full(9, 7, 7, 9, true), expected_two);
+        assert_eq!(points(Segment::new(9, 7, 7, 9)), expected_two);
         // bottom right to top left
-        assert_eq!(get_points_on_line_full(3, 3, 1, 1, true), expected_three);
+        assert_eq!(points(Segment::new(3, 3, 1, 1)), expected_three);
         // top right to bottom left
-        assert_eq!(get_points_on_line_full(7, 9, 9, 7, true), expected_four);
+        assert_eq!(points(Segment::new(7, 9, 9, 7)), expected_four);
     }
 
     #[test]
-    fn test_sparse_hydro_map() {
-        let mut map = SparseHydroMap {
-            data: FxHashMap::default(),
-        };
-        let lines = vec![
-            get_points_on_line(0, 9, 5, 9),
-            get_points_on_line(8, 0, 0, 8),
-            get_points_on_line(9, 4, 3, 4),
-            get_points_on_line(2, 2, 2, 1),
-            get_points_on_line(7, 0, 7, 4),
-            get_points_on_line(6, 4, 2, 0),
-            get_points_on_line(0, 9, 2, 9),
-            get_points_on_line(3, 4, 1, 4),
-            get_points_on_line(0, 0, 8, 8),
-            get_points_on_line(5, 5, 8, 2),
-        ];
-        for line in lines {
-            map.mark_line(line);
-        }
+    fn test_overlaps() {
         // Result should be:
         // 0,9   1,9,   2,9  3,4  8,4
-        assert_eq!(map.get_points_with_gte(2).len(), 5);
+        assert_eq!(count_overlaps(&example(), OverlapMode::Axis), 5);
     }
 
     #[test]
-    fn test_sparse_hydro_map_with_diags() {
-        let mut map = SparseHydroMap {
-            data: FxHashMap::default(),
-        };
-        let lines = vec![
-            get_points_on_line_full(0, 9, 5, 9, true),
-            get_points_on_line_full(8, 0, 0, 8, true),
-            get_points_on_line_full(9, 4, 3, 4, true),
-            get_points_on_line_full(2, 2, 2, 1, true),
-            get_points_on_line_full(7, 0, 7, 4, true),
-            get_points_on_line_full(6, 4, 2, 0, true),
-            get_points_on_line_full(0, 9, 2, 9, true),
-            get_points_on_line_full(3, 4, 1, 4, true),
-            get_points_on_line_full(0, 0, 8, 8, true),
-            get_points_on_line_full(5, 5, 8, 2, true),
+    fn test_overlaps_with_diags() {
+        assert_eq!(count_overlaps(&example(), OverlapMode::Diagonal), 12);
+    }
+
+    fn count_by_marking(segments: &[Segment], mode: OverlapMode) -> u64 {
+        let mut marked: FxHashMap<(i64, i64), usize> = FxHashMap::default();
+        for segment in segments.iter().filter(|s| s.is_in(mode)) {
+            for point in segment.points() {
+                *marked.entry(point).or_default() += 1;
+            }
+        }
+        return marked.values().filter(|n| **n >= 2).count() as u64;
+    }
+
+    #[test]
+    fn test_overlaps_of_two_lines_cross() {
+        // (-9, 14) is in the overlap along y = 14 and the one along x = -9.
+        let segments = vec![
+            Segment::new(-4, 14, -17, 14),
+            Segment::new(-7, 14, -21, 14),
+            Segment::new(-9, 16, -9, 11),
+            Segment::new(-9, 5, -9, 16),
         ];
-        for line in lines {
-            map.mark_line(line);
+        assert_eq!(count_by_marking(&segments, OverlapMode::Axis), 16);
+        assert_eq!(count_overlaps(&segments, OverlapMode::Axis), 16);
+    }
+
+    #[test]
+    fn test_overlaps_match_marking_points() {
+        let mut rng = filelib::TestRng::new(5);
+        for round in 0..3000 {
+            let mut segments = Vec::new();
+            for _ in 0..(round % 41) {
+                let (x, y) = (rng.between(-20, 20), rng.between(-20, 20));
+                // Mostly lines the puzzle would have, with some at other angles and some points.
+                // Plenty of them are horizontal or vertical, so overlaps cross other overlaps.
+                let (dx, dy) = match rng.below(10) {
+                    0..=2 => (rng.between(-15, 15), 0),
+                    3..=5 => (0, rng.between(-15, 15)),
+                    6 | 7 => {
+                        let d = rng.between(-15, 15);
+                        (d, d * rng.between(0, 1) * 2 - d)
+                    }
+                    8 => (0, 0),
+                    _ => (rng.between(-15, 15), rng.between(-15, 15)),
+                };
+                segments.push(Segment::new(x, y, x + dx, y + dy));
+            }
+            for mode in [
+                OverlapMode::Axis,
+                OverlapMode::Diagonal,
+                OverlapMode::AnyAngle,
+            ] {
+                assert_eq!(
+                    count_overlaps(&segments, mode),
+                    count_by_marking(&segments, mode),
+                    "{:?} {:?}",
+                    mode,
+                    segments
+                );
+            }
         }
-        assert_eq!(map.get_points_with_gte(2).len(), 12);
     }
 }
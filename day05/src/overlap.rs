@@ -0,0 +1,300 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Which lines count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapMode {
+    /// Horizontal and vertical lines only.
+    Axis,
+    /// Horizontal, vertical and 45 degree lines.
+    Diagonal,
+    /// Every line, at whatever angle, covering the whole number points it passes through.
+    AnyAngle,
+}
+
+/// A line of vents from `start` to `end`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub start: (i64, i64),
+    pub end: (i64, i64),
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    return a;
+}
+
+// Which infinite line a segment is on: its smallest whole step, pointing right (or down if
+// it's vertical), and b*x - a*y, which is the same all along the line.
+type LineKey = (i64, i64, i128);
+
+impl Segment {
+    pub fn new(x1: i64, y1: i64, x2: i64, y2: i64) -> Self {
+        return Segment {
+            start: (x1, y1),
+            end: (x2, y2),
+        };
+    }
+
+    /// Whether the segment is in a mode. A single point is in all of them.
+    pub fn is_in(&self, mode: OverlapMode) -> bool {
+        let dx = (self.end.0 - self.start.0).abs();
+        let dy = (self.end.1 - self.start.1).abs();
+        return match mode {
+            OverlapMode::Axis => dx == 0 || dy == 0,
+            OverlapMode::Diagonal => dx == 0 || dy == 0 || dx == dy,
+            OverlapMode::AnyAngle => true,
+        };
+    }
+
+    // The smallest whole step along the segment, and how many of them it takes.
+    fn step(&self) -> ((i64, i64), i64) {
+        let dx = self.end.0 - self.start.0;
+        let dy = self.end.1 - self.start.1;
+        let g = gcd(dx, dy);
+        if g == 0 {
+            return ((0, 0), 0);
+        }
+        return ((dx / g, dy / g), g);
+    }
+
+    /// Every whole number point on the segment, from start to end.
+    ///
+    /// ```
+    /// use day05::Segment;
+    /// let points: Vec<(i64, i64)> = Segment::new(9, 7, 7, 9).points().collect();
+    /// assert_eq!(points, vec![(9, 7), (8, 8), (7, 9)]);
+    /// let points: Vec<(i64, i64)> = Segment::new(0, 0, 4, 2).points().collect();
+    /// assert_eq!(points, vec![(0, 0), (2, 1), (4, 2)]);
+    /// ```
+    pub fn points(&self) -> impl Iterator<Item = (i64, i64)> {
+        let ((sx, sy), steps) = self.step();
+        let (x, y) = self.start;
+        return (0..=steps).map(move |i| (x + sx * i, y + sy * i));
+    }
+
+    // The line the segment is on. Single points are put on a horizontal line.
+    fn line(&self) -> LineKey {
+        let ((mut a, mut b), _) = self.step();
+        if (a, b) == (0, 0) {
+            (a, b) = (1, 0);
+        }
+        if a < 0 || (a == 0 && b < 0) {
+            (a, b) = (-a, -b);
+        }
+        return (
+            a,
+            b,
+            b as i128 * self.start.0 as i128 - a as i128 * self.start.1 as i128,
+        );
+    }
+
+    // Where a point on the line is along it, counting whole steps.
+    fn position(line: LineKey, point: (i64, i64)) -> i64 {
+        let (a, b) = (line.0 as i128, line.1 as i128);
+        // Points a step apart are a*a + b*b apart here, and all leave the same remainder.
+        return (a * point.0 as i128 + b * point.1 as i128).div_euclid(a * a + b * b) as i64;
+    }
+
+    fn span(&self, line: LineKey) -> (i64, i64) {
+        let s = Segment::position(line, self.start);
+        let e = Segment::position(line, self.end);
+        return (s.min(e), s.max(e));
+    }
+
+    fn contains(&self, point: (i64, i64)) -> bool {
+        let (x, y) = point;
+        let (dx, dy) = (self.end.0 - self.start.0, self.end.1 - self.start.1);
+        let (px, py) = (x - self.start.0, y - self.start.1);
+        if dx as i128 * py as i128 - dy as i128 * px as i128 != 0 {
+            return false;
+        }
+        let ((sx, sy), steps) = self.step();
+        if steps == 0 {
+            return (px, py) == (0, 0);
+        }
+        // On the line, so check it's a whole number of steps in, and not past the end.
+        let i = if sx != 0 { px / sx } else { py / sy };
+        return (sx * i, sy * i) == (px, py) && (0..=steps).contains(&i);
+    }
+
+    // The whole number point two segments on different lines cross at, if there is one.
+    fn crossing(&self, other: &Segment) -> Option<(i64, i64)> {
+        if self.start == self.end {
+            return Some(self.start).filter(|p| other.contains(*p));
+        }
+        if other.start == other.end {
+            return Some(other.start).filter(|p| self.contains(*p));
+        }
+        let p = (self.start.0 as i128, self.start.1 as i128);
+        let r = (
+            (self.end.0 - self.start.0) as i128,
+            (self.end.1 - self.start.1) as i128,
+        );
+        let q = (other.start.0 as i128, other.start.1 as i128);
+        let s = (
+            (other.end.0 - other.start.0) as i128,
+            (other.end.1 - other.start.1) as i128,
+        );
+        let cross = |a: (i128, i128), b: (i128, i128)| a.0 * b.1 - a.1 * b.0;
+        let denom = cross(r, s);
+        if denom == 0 {
+            return None;
+        }
+        let qp = (q.0 - p.0, q.1 - p.1);
+        // The crossing is t of the way along self and u of the way along other, with both
+        // over denom.
+        let (mut t, mut u, mut denom) = (cross(qp, s), cross(qp, r), denom);
+        if denom < 0 {
+            (t, u, denom) = (-t, -u, -denom);
+        }
+        if t < 0 || t > denom || u < 0 || u > denom {
+            return None;
+        }
+        let x = p.0 * denom + t * r.0;
+        let y = p.1 * denom + t * r.1;
+        if x % denom != 0 || y % denom != 0 {
+            return None;
+        }
+        return Some(((x / denom) as i64, (y / denom) as i64));
+    }
+}
+
+impl From<(i32, i32, i32, i32)> for Segment {
+    fn from(coords: (i32, i32, i32, i32)) -> Self {
+        let (x1, y1, x2, y2) = coords;
+        return Segment::new(x1 as i64, y1 as i64, x2 as i64, y2 as i64);
+    }
+}
+
+// Where along a line two or more spans overlap, as merged inclusive ranges.
+fn overlapping(spans: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut events: Vec<(i64, i32)> = Vec::new();
+    for (lo, hi) in spans.iter() {
+        events.push((*lo, 1));
+        events.push((*hi + 1, -1));
+    }
+    events.sort_unstable();
+    let mut result: Vec<(i64, i64)> = Vec::new();
+    let mut depth = 0;
+    let mut since = 0;
+    for (at, change) in events {
+        let was = depth;
+        depth += change;
+        if was < 2 && depth >= 2 {
+            since = at;
+        } else if was >= 2 && depth < 2 && at > since {
+            match result.last_mut() {
+                Some(last) if last.1 + 1 == since => last.1 = at - 1,
+                _ => result.push((since, at - 1)),
+            }
+        }
+    }
+    return result;
+}
+
+/// Count the whole number points covered by at least two segments, without marking out
+/// every point.
+///
+/// Segments on the same line are lined up and overlapped in one dimension, so long lines
+/// cost no more than short ones. Crossings come from sweeping across in x, keeping the
+/// segments that could still be crossed in a heap by where they end, so only segments
+/// sharing some x are ever compared.
+///
+/// ```
+/// use day05::{count_overlaps, OverlapMode, Segment};
+/// let segments = vec![
+///     Segment::new(0, 0, 1_000_000_000, 0),
+///     Segment::new(500_000_000, 0, 2_000_000_000, 0),
+///     Segment::new(0, 0, 1_000_000_000, 1_000_000_000),
+///     Segment::new(3, -1, 3, 5),
+///     Segment::new(0, 6, 6, 0),
+/// ];
+/// // The long overlap, and the vertical line crossing it at (3, 0).
+/// assert_eq!(count_overlaps(&segments, OverlapMode::Axis), 500_000_001 + 1);
+/// // Then the diagonals add (0, 0), (3, 3), where three lines cross, and (6, 0).
+/// assert_eq!(count_overlaps(&segments, OverlapMode::Diagonal), 500_000_001 + 4);
+///
+/// // These two only cross halfway between points.
+/// let segments = vec![Segment::new(0, 0, 1, 1), Segment::new(0, 1, 1, 0)];
+/// assert_eq!(count_overlaps(&segments, OverlapMode::Diagonal), 0);
+/// // And these at (2, 1), which the second only reaches in AnyAngle.
+/// let segments = vec![Segment::new(0, 0, 4, 2), Segment::new(2, 0, 2, 5)];
+/// assert_eq!(count_overlaps(&segments, OverlapMode::Diagonal), 0);
+/// assert_eq!(count_overlaps(&segments, OverlapMode::AnyAngle), 1);
+/// ```
+pub fn count_overlaps(segments: &[Segment], mode: OverlapMode) -> u64 {
+    let segments: Vec<&Segment> = segments.iter().filter(|s| s.is_in(mode)).collect();
+
+    // Overlaps along each line.
+    let mut lines: FxHashMap<LineKey, Vec<(i64, i64)>> = FxHashMap::default();
+    for segment in segments.iter() {
+        let line = segment.line();
+        lines.entry(line).or_default().push(segment.span(line));
+    }
+    let mut shared: FxHashMap<LineKey, Vec<(i64, i64)>> = FxHashMap::default();
+    let mut total: u64 = 0;
+    for (line, spans) in lines.iter() {
+        let overlaps = overlapping(spans);
+        total += overlaps
+            .iter()
+            .map(|(lo, hi)| (hi - lo + 1) as u64)
+            .sum::<u64>();
+        if !overlaps.is_empty() {
+            shared.insert(*line, overlaps);
+        }
+    }
+    let already_counted = |line: LineKey, point: (i64, i64)| -> bool {
+        let overlaps = match shared.get(&line) {
+            Some(overlaps) => overlaps,
+            None => return false,
+        };
+        let at = Segment::position(line, point);
+        let i = overlaps.partition_point(|(_, hi)| *hi < at);
+        return i < overlaps.len() && overlaps[i].0 <= at;
+    };
+
+    // Crossings between lines.
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by_key(|i| segments[*i].start.0.min(segments[*i].end.0));
+    let mut active: BinaryHeap<Reverse<(i64, usize)>> = BinaryHeap::new();
+    // Every line through each crossing.
+    let mut crossings: FxHashMap<(i64, i64), FxHashSet<LineKey>> = FxHashMap::default();
+    for i in order {
+        let segment = segments[i];
+        let left = segment.start.0.min(segment.end.0);
+        while let Some(Reverse((right, _))) = active.peek() {
+            if *right >= left {
+                break;
+            }
+            active.pop();
+        }
+        for Reverse((_, j)) in active.iter() {
+            let other = segments[*j];
+            let (line, other_line) = (segment.line(), other.line());
+            if line == other_line {
+                continue;
+            }
+            if let Some(point) = segment.crossing(other) {
+                let lines = crossings.entry(point).or_default();
+                lines.insert(line);
+                lines.insert(other_line);
+            }
+        }
+        active.push(Reverse((segment.start.0.max(segment.end.0), i)));
+    }
+    // Each crossing counts once, but it's already been counted once for every line whose
+    // overlaps it's in.
+    for (point, lines) in crossings {
+        let times_counted = lines
+            .iter()
+            .filter(|line| already_counted(**line, point))
+            .count() as u64;
+        total = total + 1 - times_counted;
+    }
+    return total;
+}
@@ -1,5 +1,7 @@
 extern crate filelib;
+mod model;
 
+pub use crate::model::FishModel;
 pub use filelib::load;
 pub use filelib::parse_csv_i32_lines;
 
@@ -39,33 +41,6 @@ fn simple_simulation(input: &Vec<i32>, num_cycle: usize) -> Vec<LanternFish> {
     return cur_cycle;
 }
 
-fn array_simulation(input: &Vec<i32>, num_cycle: usize) -> usize {
-    // instead of simulating each fish as an object, use a simple array of numbers
-    let mut fish: [usize; 7] = [0; 7];
-    let mut new_fish: [usize; 7] = [0; 7];
-    for x in input {
-        // initial seeding
-        let timer: usize = (*x).try_into().unwrap();
-        fish[timer] += 1;
-    }
-    for day in 0..num_cycle {
-        // the fish on this timer are going to be the ones that do anything
-        let num_fish_new = fish[day % 7];
-        // There are essentially two fields we need to worry about here
-        // (day + 2 ) % 7 is when these new fish should be considered, basically
-        // 2 days after this fish, this will be added to to the fish to worry about.
-        new_fish[(day + 2) % 7] = num_fish_new;
-        // Update the number of fish on the next iteration of this value, with
-        // thew number of new fish to care about.
-        fish[day % 7] += new_fish[day % 7];
-        // Clear out the number here to indicate we have used it.
-        new_fish[day % 7] = 0;
-    }
-    let mut num_fish: usize = fish.iter().sum::<usize>();
-    num_fish += new_fish.iter().sum::<usize>();
-    return num_fish;
-}
-
 /// Solution to the first puzzle.
 ///
 /// Likely this can be mathmatically modelled, but instead, going to create fish
@@ -84,7 +59,7 @@ pub fn puzzle_a(input: &Vec<i32>) -> usize {
 /// Solution to the second puzzle.
 ///
 /// 256 is way too slow, runs for a long time just to do this test. As such
-/// we are switching to using an array simulation.
+/// we are switching to counting fish per timer with FishModel.
 ///
 /// ```
 /// let input = vec![3, 4, 3, 1, 2];
@@ -92,8 +67,8 @@ pub fn puzzle_a(input: &Vec<i32>) -> usize {
 /// ```
 pub fn puzzle_b(input: &Vec<i32>) -> usize {
     let total_cycles = 256;
-    let finished_sim = array_simulation(input, total_cycles);
-    return finished_sim;
+    let finished_sim = FishModel::puzzle().population(input, total_cycles);
+    return finished_sim.unwrap() as usize;
 }
 
 #[cfg(test)]
@@ -128,13 +103,28 @@ mod tests {
     }
 
     #[test]
-    fn test_array_simulation() {
+    fn test_model_population() {
         let start = vec![3, 4, 3, 1, 2];
-        assert_eq!(array_simulation(&start, 0), 5);
-        assert_eq!(array_simulation(&start, 1), 5);
-        assert_eq!(array_simulation(&start, 2), 6);
-        assert_eq!(array_simulation(&start, 8), 10);
-        assert_eq!(array_simulation(&start, 18), 26);
-        assert_eq!(array_simulation(&start, 80), 5934);
+        let model = FishModel::puzzle();
+        assert_eq!(model.population(&start, 0), Ok(5));
+        assert_eq!(model.population(&start, 1), Ok(5));
+        assert_eq!(model.population(&start, 2), Ok(6));
+        assert_eq!(model.population(&start, 8), Ok(10));
+        assert_eq!(model.population(&start, 18), Ok(26));
+        assert_eq!(model.population(&start, 80), Ok(5934));
+    }
+
+    #[test]
+    fn test_model_matches_simulation() {
+        let start = vec![3, 4, 3, 1, 2];
+        let model = FishModel::puzzle();
+        for days in 0..60 {
+            let simulated = simple_simulation(&start, days).len() as u128;
+            assert_eq!(model.population(&start, days as u64), Ok(simulated));
+            assert_eq!(
+                model.population_mod(&start, days as u64, 97),
+                Ok((simulated % 97) as u64)
+            );
+        }
     }
 }
@@ -0,0 +1,257 @@
+use num_bigint::BigUint;
+
+type Matrix<T> = Vec<Vec<T>>;
+
+/// How lanternfish breed: when a fish's timer passes 0 it goes back to `reset_timer` and
+/// `offspring` new fish appear with `newborn_timer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FishModel {
+    pub reset_timer: usize,
+    pub newborn_timer: usize,
+    pub offspring: u64,
+}
+
+// The numbers the populations are counted in, so the same matrix code can count in a u128,
+// a BigUint or modulo something. None means it's overflowed.
+trait Arithmetic {
+    type Value: Clone;
+    fn zero(&self) -> Self::Value;
+    fn one(&self) -> Self::Value;
+    fn number(&self, n: u64) -> Self::Value;
+    fn add(&self, a: &Self::Value, b: &Self::Value) -> Option<Self::Value>;
+    fn mul(&self, a: &Self::Value, b: &Self::Value) -> Option<Self::Value>;
+}
+
+struct Checked;
+struct Big;
+struct Modular(u64);
+
+impl Arithmetic for Checked {
+    type Value = u128;
+    fn zero(&self) -> u128 {
+        return 0;
+    }
+    fn one(&self) -> u128 {
+        return 1;
+    }
+    fn number(&self, n: u64) -> u128 {
+        return n as u128;
+    }
+    fn add(&self, a: &u128, b: &u128) -> Option<u128> {
+        return a.checked_add(*b);
+    }
+    fn mul(&self, a: &u128, b: &u128) -> Option<u128> {
+        return a.checked_mul(*b);
+    }
+}
+
+impl Arithmetic for Big {
+    type Value = BigUint;
+    fn zero(&self) -> BigUint {
+        return BigUint::from(0u32);
+    }
+    fn one(&self) -> BigUint {
+        return BigUint::from(1u32);
+    }
+    fn number(&self, n: u64) -> BigUint {
+        return BigUint::from(n);
+    }
+    fn add(&self, a: &BigUint, b: &BigUint) -> Option<BigUint> {
+        return Some(a + b);
+    }
+    fn mul(&self, a: &BigUint, b: &BigUint) -> Option<BigUint> {
+        return Some(a * b);
+    }
+}
+
+impl Arithmetic for Modular {
+    type Value = u64;
+    fn zero(&self) -> u64 {
+        return 0;
+    }
+    fn one(&self) -> u64 {
+        return 1 % self.0;
+    }
+    fn number(&self, n: u64) -> u64 {
+        return n % self.0;
+    }
+    fn add(&self, a: &u64, b: &u64) -> Option<u64> {
+        return Some(((*a as u128 + *b as u128) % self.0 as u128) as u64);
+    }
+    fn mul(&self, a: &u64, b: &u64) -> Option<u64> {
+        return Some(((*a as u128 * *b as u128) % self.0 as u128) as u64);
+    }
+}
+
+fn matrix_mul<A: Arithmetic>(
+    arith: &A,
+    left: &Matrix<A::Value>,
+    right: &Matrix<A::Value>,
+) -> Option<Matrix<A::Value>> {
+    let size = left.len();
+    let mut result = vec![vec![arith.zero(); size]; size];
+    for i in 0..size {
+        for k in 0..size {
+            for j in 0..size {
+                let product = arith.mul(&left[i][k], &right[k][j])?;
+                result[i][j] = arith.add(&result[i][j], &product)?;
+            }
+        }
+    }
+    return Some(result);
+}
+
+fn matrix_pow<A: Arithmetic>(
+    arith: &A,
+    mut base: Matrix<A::Value>,
+    mut exponent: u64,
+) -> Option<Matrix<A::Value>> {
+    let size = base.len();
+    let mut result = vec![vec![arith.zero(); size]; size];
+    for (i, row) in result.iter_mut().enumerate() {
+        row[i] = arith.one();
+    }
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = matrix_mul(arith, &result, &base)?;
+        }
+        exponent >>= 1;
+        // Squaring once more than needed could overflow when the answer doesn't.
+        if exponent > 0 {
+            base = matrix_mul(arith, &base, &base)?;
+        }
+    }
+    return Some(result);
+}
+
+impl FishModel {
+    /// Back to 6 and one newborn at 8, like the puzzle.
+    pub fn puzzle() -> Self {
+        return FishModel {
+            reset_timer: 6,
+            newborn_timer: 8,
+            offspring: 1,
+        };
+    }
+
+    /// How many timer values there are, from 0 to the highest a fish can have.
+    pub fn states(&self) -> usize {
+        return self.reset_timer.max(self.newborn_timer) + 1;
+    }
+
+    /// How many fish have each timer value.
+    ///
+    /// ```
+    /// let model = day06::FishModel::puzzle();
+    /// assert_eq!(model.timer_counts(&[3, 4, 3, 1, 2]), Ok(vec![0, 1, 1, 2, 1, 0, 0, 0, 0]));
+    /// assert!(model.timer_counts(&[9]).is_err());
+    /// ```
+    pub fn timer_counts(&self, timers: &[i32]) -> Result<Vec<u64>, String> {
+        let mut counts = vec![0; self.states()];
+        for timer in timers.iter() {
+            if *timer < 0 || *timer as usize >= self.states() {
+                return Err(format!(
+                    "A fish's timer can't be {}, only 0 to {}",
+                    timer,
+                    self.states() - 1
+                ));
+            }
+            counts[*timer as usize] += 1;
+        }
+        return Ok(counts);
+    }
+
+    // transition[to][from] is how many fish with timer `to` one fish with timer `from`
+    // turns into the next day.
+    fn transition<A: Arithmetic>(&self, arith: &A) -> Matrix<A::Value> {
+        let size = self.states();
+        let mut counts = vec![vec![0u64; size]; size];
+        for from in 1..size {
+            counts[from - 1][from] = 1;
+        }
+        counts[self.reset_timer][0] += 1;
+        counts[self.newborn_timer][0] += self.offspring;
+        return counts
+            .iter()
+            .map(|row| row.iter().map(|n| arith.number(*n)).collect())
+            .collect();
+    }
+
+    fn count<A: Arithmetic>(
+        &self,
+        arith: &A,
+        timers: &[i32],
+        days: u64,
+    ) -> Result<A::Value, String> {
+        let start = self.timer_counts(timers)?;
+        let overflow = || format!("Too many fish after {} days to count", days);
+        let after = matrix_pow(arith, self.transition(arith), days).ok_or_else(overflow)?;
+        let mut total = arith.zero();
+        for row in after.iter() {
+            for (from, count) in start.iter().enumerate() {
+                let fish = arith
+                    .mul(&row[from], &arith.number(*count))
+                    .ok_or_else(overflow)?;
+                total = arith.add(&total, &fish).ok_or_else(overflow)?;
+            }
+        }
+        return Ok(total);
+    }
+
+    /// How many fish there are after `days`, by raising the day to day transition matrix to
+    /// that power, so it only takes a handful of matrix multiplications even for huge
+    /// numbers of days. Gives an Err if a timer is out of range or the count won't fit.
+    ///
+    /// ```
+    /// use day06::FishModel;
+    /// let model = FishModel::puzzle();
+    /// assert_eq!(model.population(&[3, 4, 3, 1, 2], 18), Ok(26));
+    /// assert_eq!(model.population(&[3, 4, 3, 1, 2], 256), Ok(26984457539));
+    /// assert!(model.population(&[3, 4, 3, 1, 2], 1_000).is_err());
+    ///
+    /// // Twins every 3 days, starting at 2.
+    /// let twins = FishModel { reset_timer: 2, newborn_timer: 2, offspring: 2 };
+    /// assert_eq!(twins.population(&[0], 1), Ok(3));
+    /// assert_eq!(twins.population(&[0], 4), Ok(9));
+    /// ```
+    pub fn population(&self, timers: &[i32], days: u64) -> Result<u128, String> {
+        return self.count(&Checked, timers, days);
+    }
+
+    /// The same as `population`, but counted with no upper limit. The numbers grow with
+    /// the days, so this is for thousands of days rather than trillions.
+    ///
+    /// ```
+    /// use day06::FishModel;
+    /// let model = FishModel::puzzle();
+    /// let fish = model.population_big(&[3, 4, 3, 1, 2], 1_000).unwrap();
+    /// assert_eq!(fish.bits(), 129);
+    /// assert!(fish > num_bigint::BigUint::from(u128::MAX));
+    /// ```
+    pub fn population_big(&self, timers: &[i32], days: u64) -> Result<BigUint, String> {
+        return self.count(&Big, timers, days);
+    }
+
+    /// How many fish there are after `days`, modulo `modulus`. Works for any number of days.
+    ///
+    /// ```
+    /// use day06::FishModel;
+    /// let model = FishModel::puzzle();
+    /// let start = [3, 4, 3, 1, 2];
+    /// let modulus = 1_000_000_007;
+    /// assert_eq!(model.population_mod(&start, 256, modulus), Ok(26984457539 % modulus));
+    /// let big = model.population_big(&start, 5_000).unwrap();
+    /// assert_eq!(
+    ///     model.population_mod(&start, 5_000, modulus).unwrap(),
+    ///     (big % modulus).try_into().unwrap()
+    /// );
+    /// assert!(model.population_mod(&start, 1_000_000_000_000, modulus).is_ok());
+    /// assert!(model.population_mod(&start, 10, 0).is_err());
+    /// ```
+    pub fn population_mod(&self, timers: &[i32], days: u64, modulus: u64) -> Result<u64, String> {
+        if modulus == 0 {
+            return Err("Can't count modulo 0".to_string());
+        }
+        return self.count(&Modular(modulus), timers, days);
+    }
+}
@@ -0,0 +1,242 @@
+/// Crab positions, sorted and with running totals so sums over them don't need a full pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crabs {
+    sorted: Vec<i64>,
+    // prefix[i] is the sum of the first i positions, prefix_squares[i] of their squares.
+    prefix: Vec<i128>,
+    prefix_squares: Vec<i128>,
+}
+
+/// Where the crabs should line up, and the fuel it takes them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alignment {
+    pub position: i64,
+    pub fuel: u64,
+}
+
+// Fuel totals saturate rather than overflow.
+fn clamp_fuel(fuel: i128) -> u64 {
+    return fuel.clamp(0, u64::MAX as i128) as u64;
+}
+
+impl Crabs {
+    pub fn new(positions: &[i32]) -> Self {
+        let mut sorted: Vec<i64> = positions.iter().map(|p| *p as i64).collect();
+        sorted.sort_unstable();
+        let mut prefix = vec![0];
+        let mut prefix_squares = vec![0];
+        for p in sorted.iter() {
+            let p = *p as i128;
+            prefix.push(prefix.last().unwrap() + p);
+            prefix_squares.push(prefix_squares.last().unwrap() + p * p);
+        }
+        return Crabs {
+            sorted,
+            prefix,
+            prefix_squares,
+        };
+    }
+
+    pub fn len(&self) -> usize {
+        return self.sorted.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.sorted.is_empty();
+    }
+
+    /// Positions, smallest first.
+    pub fn positions(&self) -> &[i64] {
+        return &self.sorted;
+    }
+
+    /// The lower median position.
+    pub fn median(&self) -> Option<i64> {
+        if self.is_empty() {
+            return None;
+        }
+        return Some(self.sorted[(self.len() - 1) / 2]);
+    }
+
+    /// The mean position, rounded down.
+    pub fn mean(&self) -> Option<i64> {
+        if self.is_empty() {
+            return None;
+        }
+        return Some(self.prefix[self.len()].div_euclid(self.len() as i128) as i64);
+    }
+
+    // How many crabs are left of `position`, and the sum and sum of squares of each side.
+    fn split(&self, position: i64) -> (i128, [i128; 2], [i128; 2]) {
+        let below = self.sorted.partition_point(|p| *p < position);
+        let n = self.len();
+        return (
+            below as i128,
+            [self.prefix[below], self.prefix[n] - self.prefix[below]],
+            [
+                self.prefix_squares[below],
+                self.prefix_squares[n] - self.prefix_squares[below],
+            ],
+        );
+    }
+
+    /// Total distance from every crab to `position`.
+    ///
+    /// ```
+    /// let crabs = day07::Crabs::new(&[16, 1, 2, 0, 4, 2, 7, 1, 2, 14]);
+    /// assert_eq!(crabs.distance_sum(2), 37);
+    /// assert_eq!(crabs.distance_sum(10), 71);
+    /// ```
+    pub fn distance_sum(&self, position: i64) -> u64 {
+        let (below, sums, _) = self.split(position);
+        let above = self.len() as i128 - below;
+        let p = position as i128;
+        return clamp_fuel(p * below - sums[0] + sums[1] - p * above);
+    }
+
+    /// Total squared distance from every crab to `position`.
+    ///
+    /// ```
+    /// let crabs = day07::Crabs::new(&[1, 4, 6]);
+    /// assert_eq!(crabs.square_sum(4), 9 + 0 + 4);
+    /// ```
+    pub fn square_sum(&self, position: i64) -> u64 {
+        let (_, sums, squares) = self.split(position);
+        let p = position as i128;
+        let n = self.len() as i128;
+        // Expanding (p - x)^2 over both sides at once.
+        return clamp_fuel(n * p * p - 2 * p * (sums[0] + sums[1]) + squares[0] + squares[1]);
+    }
+
+    /// The best place to line up for a given cost. None if there are no crabs.
+    ///
+    /// Each cost narrows down where to look, then it's a ternary search over that, which
+    /// works for any cost that's convex in the distance. If positions tie, it's one of them.
+    ///
+    /// ```
+    /// use day07::{Alignment, Crabs, Custom, Linear, Quadratic, Triangular};
+    /// let crabs = Crabs::new(&[16, 1, 2, 0, 4, 2, 7, 1, 2, 14]);
+    /// assert_eq!(crabs.align(&Linear), Some(Alignment { position: 2, fuel: 37 }));
+    /// assert_eq!(crabs.align(&Triangular), Some(Alignment { position: 5, fuel: 168 }));
+    /// assert_eq!(crabs.align(&Quadratic), Some(Alignment { position: 5, fuel: 291 }));
+    /// // Cheap to start with, then dearer past 3 steps.
+    /// let steep = Custom(|d: u64| if d <= 3 { d } else { 3 + 10 * (d - 3) });
+    /// assert_eq!(crabs.align(&steep), Some(Alignment { position: 4, fuel: 194 }));
+    /// assert_eq!(Crabs::new(&[]).align(&Linear), None);
+    /// ```
+    pub fn align<C: FuelCost + ?Sized>(&self, cost: &C) -> Option<Alignment> {
+        if self.is_empty() {
+            return None;
+        }
+        let (mut lo, mut hi) = cost.search_range(self);
+        while hi - lo > 2 {
+            let third = (hi - lo) / 3;
+            let (m1, m2) = (lo + third, hi - third);
+            let (f1, f2) = (cost.total(self, m1), cost.total(self, m2));
+            if f1 < f2 {
+                hi = m2 - 1;
+            } else if f1 > f2 {
+                lo = m1 + 1;
+            } else {
+                // Convex, so a minimum has to be somewhere between the two.
+                lo = m1;
+                hi = m2;
+            }
+        }
+        return (lo..=hi)
+            .map(|position| Alignment {
+                position,
+                fuel: cost.total(self, position),
+            })
+            .min_by_key(|a| (a.fuel, a.position));
+    }
+}
+
+/// How much fuel it takes a crab to move some distance.
+///
+/// Costs should be convex: each extra step costs at least as much as the one before.
+pub trait FuelCost {
+    fn cost(&self, distance: u64) -> u64;
+
+    /// Fuel for every crab to get to `position`. By default this adds up `cost` crab by crab.
+    fn total(&self, crabs: &Crabs, position: i64) -> u64 {
+        return crabs.positions().iter().fold(0u64, |fuel, p| {
+            return fuel.saturating_add(self.cost(p.abs_diff(position)));
+        });
+    }
+
+    /// The positions the best one has to be between. By default that's anywhere between
+    /// the outermost crabs.
+    fn search_range(&self, crabs: &Crabs) -> (i64, i64) {
+        let positions = crabs.positions();
+        return (positions[0], positions[positions.len() - 1]);
+    }
+}
+
+/// A unit of fuel per step. The median is always a best position.
+#[derive(Debug, Clone, Copy)]
+pub struct Linear;
+
+/// One more unit of fuel for each step than the last, so n steps cost n(n+1)/2. The best
+/// position is always within a step of the mean.
+#[derive(Debug, Clone, Copy)]
+pub struct Triangular;
+
+/// Fuel is the distance squared.
+#[derive(Debug, Clone, Copy)]
+pub struct Quadratic;
+
+/// Any other cost, as a closure from distance to fuel.
+#[derive(Debug, Clone, Copy)]
+pub struct Custom<F: Fn(u64) -> u64>(pub F);
+
+impl FuelCost for Linear {
+    fn cost(&self, distance: u64) -> u64 {
+        return distance;
+    }
+
+    fn total(&self, crabs: &Crabs, position: i64) -> u64 {
+        return crabs.distance_sum(position);
+    }
+
+    fn search_range(&self, crabs: &Crabs) -> (i64, i64) {
+        let median = crabs.median().unwrap();
+        return (median, median);
+    }
+}
+
+impl FuelCost for Triangular {
+    fn cost(&self, distance: u64) -> u64 {
+        return distance.saturating_mul(distance.saturating_add(1)) / 2;
+    }
+
+    fn total(&self, crabs: &Crabs, position: i64) -> u64 {
+        let both = crabs.square_sum(position) as u128 + crabs.distance_sum(position) as u128;
+        return clamp_fuel((both / 2) as i128);
+    }
+
+    fn search_range(&self, crabs: &Crabs) -> (i64, i64) {
+        // The mean rounded down, so mean ±1 covers rounding up too.
+        let mean = crabs.mean().unwrap();
+        let positions = crabs.positions();
+        let lowest = positions[0];
+        let highest = positions[positions.len() - 1];
+        return ((mean - 1).max(lowest), (mean + 2).min(highest));
+    }
+}
+
+impl FuelCost for Quadratic {
+    fn cost(&self, distance: u64) -> u64 {
+        return distance.saturating_mul(distance);
+    }
+
+    fn total(&self, crabs: &Crabs, position: i64) -> u64 {
+        return crabs.square_sum(position);
+    }
+}
+
+impl<F: Fn(u64) -> u64> FuelCost for Custom<F> {
+    fn cost(&self, distance: u64) -> u64 {
+        return (self.0)(distance);
+    }
+}
@@ -0,0 +1,69 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day08::{decode_seven_segment, DisplayLayout};
+use filelib::TestRng;
+
+// Scrambled signal and output lines, from a seeded generator so every run gets the same ones.
+fn generate_lines(count: usize) -> Vec<(String, String)> {
+    let digits: Vec<u32> = DisplayLayout::seven_segment()
+        .symbols()
+        .iter()
+        .map(|(_, p)| *p)
+        .collect();
+    let mut rng = TestRng::new(2021);
+
+    let mut lines = Vec::with_capacity(count);
+    for _ in 0..count {
+        let mut wiring: Vec<char> = "abcdefg".chars().collect();
+        rng.shuffle(&mut wiring);
+        let scramble = |pattern: u32| -> String {
+            return (0..7)
+                .filter(|i| pattern & (1 << i) != 0)
+                .map(|i| wiring[i])
+                .collect();
+        };
+        let mut order: Vec<usize> = (0..10).collect();
+        rng.shuffle(&mut order);
+        let signals: Vec<String> = order.iter().map(|d| scramble(digits[*d])).collect();
+        let outputs: Vec<String> = (0..4)
+            .map(|_| scramble(digits[rng.below(10) as usize]))
+            .collect();
+        lines.push((signals.join(" "), outputs.join(" ")));
+    }
+    return lines;
+}
+
+fn decoders(c: &mut Criterion) {
+    let lines = generate_lines(1_000_000);
+    let layout = DisplayLayout::seven_segment();
+
+    let mut group = c.benchmark_group("decode");
+    group.sample_size(10);
+    group.bench_function("segment_set_million_lines", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            for (signals, outputs) in lines.iter() {
+                total += decode_seven_segment(black_box(signals), black_box(outputs)).unwrap();
+            }
+            return total;
+        })
+    });
+    // The general solver is a lot slower, so it gets a slice of them.
+    group.bench_function("layout_ten_thousand_lines", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            for (signals, outputs) in lines[..10_000].iter() {
+                let signals: Vec<&str> = signals.split(' ').collect();
+                let outputs: Vec<&str> = outputs.split(' ').collect();
+                let read = layout
+                    .read(black_box(&signals), black_box(&outputs))
+                    .unwrap();
+                total += read.concat().parse::<usize>().unwrap();
+            }
+            return total;
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, decoders);
+criterion_main!(benches);
@@ -0,0 +1,368 @@
+use rustc_hash::FxHashSet;
+
+/// Most segments a display can have, as patterns are kept as u32 bitmasks.
+pub const MAX_SEGMENTS: usize = 32;
+
+/// How many wirings `DisplayLayout::solve` collects before it stops looking for more.
+pub const MAX_AMBIGUOUS: usize = 16;
+
+/// The segments a display has, and which of them light up for each symbol it can show.
+///
+/// Patterns are bitmasks with bit `i` for the `i`th segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayLayout {
+    segments: Vec<char>,
+    symbols: Vec<(String, u32)>,
+}
+
+/// Which segment each wire really goes to, indexed the same as the layout's segments.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WireMap {
+    names: Vec<char>,
+    to: Vec<usize>,
+}
+
+/// What a set of scrambled patterns says about the wiring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoding {
+    /// Exactly one wiring fits.
+    Unique(WireMap),
+    /// More than one fits. Holds the ones found, up to `MAX_AMBIGUOUS` of them.
+    Ambiguous(Vec<WireMap>),
+    /// Nothing fits.
+    Impossible,
+}
+
+impl WireMap {
+    /// The segment a wire is really connected to.
+    pub fn get(&self, wire: char) -> Option<char> {
+        let i = self.names.iter().position(|n| *n == wire)?;
+        return Some(self.names[self.to[i]]);
+    }
+
+    /// Turn a pattern of wires into the pattern of segments they light.
+    pub fn translate(&self, wires: u32) -> u32 {
+        let mut segments = 0;
+        for (wire, segment) in self.to.iter().enumerate() {
+            if wires & (1 << wire) != 0 {
+                segments |= 1 << segment;
+            }
+        }
+        return segments;
+    }
+}
+
+impl DisplayLayout {
+    /// A display with the given segment names, and each symbol as the names of the segments
+    /// it lights. Symbols have to light different sets of segments.
+    ///
+    /// ```
+    /// use day08::DisplayLayout;
+    /// assert!(DisplayLayout::new("abc", &[("1", "ab"), ("2", "bc")]).is_ok());
+    /// assert!(DisplayLayout::new("abc", &[("1", "ab"), ("2", "ba")]).is_err());
+    /// assert!(DisplayLayout::new("abc", &[("1", "ad")]).is_err());
+    /// assert!(DisplayLayout::new("abca", &[]).is_err());
+    /// ```
+    pub fn new(segments: &str, symbols: &[(&str, &str)]) -> Result<Self, String> {
+        let segments: Vec<char> = segments.chars().collect();
+        if segments.len() > MAX_SEGMENTS {
+            return Err(format!(
+                "{} segments is more than the {} supported",
+                segments.len(),
+                MAX_SEGMENTS
+            ));
+        }
+        let unique: FxHashSet<char> = segments.iter().copied().collect();
+        if unique.len() != segments.len() {
+            return Err(format!("Segment names repeat in {:?}", segments));
+        }
+
+        let mut layout = DisplayLayout {
+            segments,
+            symbols: Vec::new(),
+        };
+        for (symbol, lit) in symbols.iter() {
+            let pattern = layout.pattern(lit)?;
+            if let Some(other) = layout.symbol_for(pattern) {
+                return Err(format!("{} and {} light the same segments", other, symbol));
+            }
+            layout.symbols.push((symbol.to_string(), pattern));
+        }
+        return Ok(layout);
+    }
+
+    /// The digits 0 to 9 on an ordinary seven segment display, named like the puzzle:
+    ///
+    /// ```text
+    ///  aaaa
+    /// b    c
+    /// b    c
+    ///  dddd
+    /// e    f
+    /// e    f
+    ///  gggg
+    /// ```
+    pub fn seven_segment() -> Self {
+        return DisplayLayout::new(
+            "abcdefg",
+            &[
+                ("0", "abcefg"),
+                ("1", "cf"),
+                ("2", "acdeg"),
+                ("3", "acdfg"),
+                ("4", "bcdf"),
+                ("5", "abdfg"),
+                ("6", "abdefg"),
+                ("7", "acf"),
+                ("8", "abcdefg"),
+                ("9", "abcdfg"),
+            ],
+        )
+        .unwrap();
+    }
+
+    /// Seven segment digits plus A, b, C, d, E and F for hex.
+    pub fn seven_segment_hex() -> Self {
+        let mut layout = DisplayLayout::seven_segment();
+        for (symbol, lit) in [
+            ("A", "abcdef"),
+            ("b", "bdefg"),
+            ("C", "abeg"),
+            ("d", "cdefg"),
+            ("E", "abdeg"),
+            ("F", "abde"),
+        ] {
+            let pattern = layout.pattern(lit).unwrap();
+            layout.symbols.push((symbol.to_string(), pattern));
+        }
+        return layout;
+    }
+
+    pub fn segments(&self) -> &[char] {
+        return &self.segments;
+    }
+
+    /// Every symbol and the segments it lights.
+    pub fn symbols(&self) -> &[(String, u32)] {
+        return &self.symbols;
+    }
+
+    /// The bitmask for some segment (or wire) names, in any order.
+    pub fn pattern(&self, lit: &str) -> Result<u32, String> {
+        let mut pattern = 0;
+        for c in lit.chars() {
+            let i = match self.segments.iter().position(|s| *s == c) {
+                Some(i) => i,
+                None => return Err(format!("{:?} isn't a segment in {:?}", c, lit)),
+            };
+            if pattern & (1 << i) != 0 {
+                return Err(format!("{:?} is in {:?} twice", c, lit));
+            }
+            pattern |= 1 << i;
+        }
+        return Ok(pattern);
+    }
+
+    /// The symbol shown when exactly these segments are lit.
+    pub fn symbol_for(&self, pattern: u32) -> Option<&str> {
+        return self
+            .symbols
+            .iter()
+            .find(|(_, p)| *p == pattern)
+            .map(|(s, _)| s.as_str());
+    }
+
+    /// Work out which wire goes to which segment from scrambled patterns, each of which has
+    /// to be one of the symbols. Repeats are fine, so outputs can be thrown in too.
+    ///
+    /// Each pattern is matched up with a symbol of the same size in turn, narrowing down the
+    /// segments every wire could go to, and backing up whenever some wire runs out.
+    ///
+    /// ```
+    /// use day08::{Decoding, DisplayLayout};
+    /// let layout = DisplayLayout::seven_segment();
+    /// let line: Vec<&str> = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab"
+    ///     .split(' ')
+    ///     .collect();
+    /// match layout.solve(&line).unwrap() {
+    ///     Decoding::Unique(wiring) => assert_eq!(wiring.get('d'), Some('a')),
+    ///     other => panic!("{:?}", other),
+    /// }
+    ///
+    /// // A 1 and a 7 can't tell most of the wires apart.
+    /// match layout.solve(&["ab", "dab"]).unwrap() {
+    ///     Decoding::Ambiguous(wirings) => assert_eq!(wirings.len(), day08::MAX_AMBIGUOUS),
+    ///     other => panic!("{:?}", other),
+    /// }
+    ///
+    /// // Nothing has three segments either side of another.
+    /// assert_eq!(layout.solve(&["ab", "cde"]).unwrap(), Decoding::Impossible);
+    /// assert!(layout.solve(&["abz"]).is_err());
+    /// ```
+    pub fn solve(&self, patterns: &[&str]) -> Result<Decoding, String> {
+        let mut unique: Vec<u32> = Vec::new();
+        for p in patterns.iter() {
+            let pattern = self.pattern(p)?;
+            if !unique.contains(&pattern) {
+                unique.push(pattern);
+            }
+        }
+        // Fewest choices first, so the search narrows down as fast as it can.
+        let choices = |p: &u32| {
+            return self
+                .symbols
+                .iter()
+                .filter(|(_, s)| s.count_ones() == p.count_ones())
+                .count();
+        };
+        unique.sort_by_key(choices);
+
+        let all = if self.segments.len() == 32 {
+            u32::MAX
+        } else {
+            (1 << self.segments.len()) - 1
+        };
+        let mut search = Search {
+            layout: self,
+            patterns: unique,
+            used: vec![false; self.symbols.len()],
+            found: Vec::new(),
+        };
+        search.assign(0, vec![all; self.segments.len()]);
+
+        let mut found = search.found;
+        return Ok(match found.len() {
+            0 => Decoding::Impossible,
+            1 => Decoding::Unique(found.remove(0)),
+            _ => Decoding::Ambiguous(found),
+        });
+    }
+
+    /// Solve the wiring from `patterns` and read off the symbols shown by `outputs`.
+    ///
+    /// ```
+    /// use day08::DisplayLayout;
+    /// let layout = DisplayLayout::seven_segment_hex();
+    /// // The usual wiring, so the patterns can be read straight off.
+    /// let patterns = vec![
+    ///     "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg",
+    ///     "abcdfg", "abcdef", "bdefg", "abeg", "cdefg", "abdeg", "abde",
+    /// ];
+    /// let read = layout.read(&patterns, &["cdefg", "abcdef", "abdeg", "abde"]).unwrap();
+    /// assert_eq!(read.concat(), "dAEF");
+    ///
+    /// // Without all of them there's no telling a 1 from a 7.
+    /// assert!(layout.read(&["cf", "acf"], &["cf"]).is_err());
+    /// ```
+    pub fn read(&self, patterns: &[&str], outputs: &[&str]) -> Result<Vec<String>, String> {
+        let mut all: Vec<&str> = patterns.to_vec();
+        all.extend_from_slice(outputs);
+        let wiring = match self.solve(&all)? {
+            Decoding::Unique(wiring) => wiring,
+            Decoding::Ambiguous(wirings) => {
+                return Err(format!(
+                    "At least {} wirings fit {:?}",
+                    wirings.len(),
+                    patterns
+                ));
+            }
+            Decoding::Impossible => return Err(format!("No wiring fits {:?}", patterns)),
+        };
+        let mut read = Vec::new();
+        for output in outputs.iter() {
+            let lit = wiring.translate(self.pattern(output)?);
+            read.push(self.symbol_for(lit).unwrap().to_string());
+        }
+        return Ok(read);
+    }
+}
+
+struct Search<'a> {
+    layout: &'a DisplayLayout,
+    patterns: Vec<u32>,
+    // Symbols already matched up with a pattern.
+    used: Vec<bool>,
+    found: Vec<WireMap>,
+}
+
+// Keep taking segments that only one wire can go to away from all the other wires. Gives
+// false if that leaves a wire with nowhere to go.
+fn propagate(candidates: &mut [u32]) -> bool {
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..candidates.len() {
+            if candidates[i] == 0 {
+                return false;
+            }
+            if candidates[i].count_ones() != 1 {
+                continue;
+            }
+            for j in 0..candidates.len() {
+                if j != i && candidates[j] & candidates[i] != 0 {
+                    candidates[j] &= !candidates[i];
+                    changed = true;
+                }
+            }
+        }
+    }
+    return true;
+}
+
+impl<'a> Search<'a> {
+    fn done(&self) -> bool {
+        return self.found.len() >= MAX_AMBIGUOUS;
+    }
+
+    // Match patterns[index..] up with symbols, given where each wire could still go.
+    fn assign(&mut self, index: usize, candidates: Vec<u32>) {
+        if index == self.patterns.len() {
+            let mut to = vec![0; candidates.len()];
+            self.wire_up(0, 0, &candidates, &mut to);
+            return;
+        }
+        let pattern = self.patterns[index];
+        for s in 0..self.layout.symbols.len() {
+            let symbol = self.layout.symbols[s].1;
+            if self.used[s] || symbol.count_ones() != pattern.count_ones() {
+                continue;
+            }
+            // Wires in the pattern go to the symbol's segments, and the rest don't.
+            let mut next = candidates.clone();
+            for (wire, c) in next.iter_mut().enumerate() {
+                if pattern & (1 << wire) != 0 {
+                    *c &= symbol;
+                } else {
+                    *c &= !symbol;
+                }
+            }
+            if !propagate(&mut next) {
+                continue;
+            }
+            self.used[s] = true;
+            self.assign(index + 1, next);
+            self.used[s] = false;
+            if self.done() {
+                return;
+            }
+        }
+    }
+
+    // Pick a different segment for each wire from what's left, recording every way to do it.
+    fn wire_up(&mut self, wire: usize, taken: u32, candidates: &[u32], to: &mut Vec<usize>) {
+        if wire == candidates.len() {
+            self.found.push(WireMap {
+                names: self.layout.segments.clone(),
+                to: to.clone(),
+            });
+            return;
+        }
+        let mut options = candidates[wire] & !taken;
+        while options != 0 && !self.done() {
+            let segment = options.trailing_zeros() as usize;
+            options &= options - 1;
+            to[wire] = segment;
+            self.wire_up(wire + 1, taken | (1 << segment), candidates, to);
+        }
+    }
+}
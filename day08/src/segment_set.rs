@@ -0,0 +1,131 @@
+/// A set of the segments a to h, one bit each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SegmentSet(u8);
+
+impl SegmentSet {
+    pub fn from_bits(bits: u8) -> Self {
+        return SegmentSet(bits);
+    }
+
+    pub fn bits(self) -> u8 {
+        return self.0;
+    }
+
+    pub fn union(self, other: SegmentSet) -> SegmentSet {
+        return SegmentSet(self.0 | other.0);
+    }
+
+    pub fn intersection(self, other: SegmentSet) -> SegmentSet {
+        return SegmentSet(self.0 & other.0);
+    }
+
+    pub fn difference(self, other: SegmentSet) -> SegmentSet {
+        return SegmentSet(self.0 & !other.0);
+    }
+
+    /// How many segments are in the set.
+    pub fn len(self) -> u32 {
+        return self.0.count_ones();
+    }
+
+    pub fn is_empty(self) -> bool {
+        return self.0 == 0;
+    }
+
+    pub fn contains(self, segment: char) -> bool {
+        return match segment {
+            'a'..='h' => self.0 & (1 << (segment as u8 - b'a')) != 0,
+            _ => false,
+        };
+    }
+}
+
+impl std::str::FromStr for SegmentSet {
+    type Err = String;
+
+    /// ```
+    /// use day08::SegmentSet;
+    /// let cf: SegmentSet = "fc".parse().unwrap();
+    /// let acf: SegmentSet = "acf".parse().unwrap();
+    /// assert_eq!(cf.bits(), 0b100100);
+    /// assert_eq!(cf.union(acf), acf);
+    /// assert_eq!(cf.intersection(acf).len(), 2);
+    /// assert_eq!(acf.difference(cf).to_string(), "a");
+    /// assert!(acf.contains('a') && !cf.contains('a'));
+    /// assert!("abz".parse::<SegmentSet>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bits = 0;
+        for c in s.bytes() {
+            if !(b'a'..=b'h').contains(&c) {
+                return Err(format!("{:?} isn't a segment from a to h", c as char));
+            }
+            bits |= 1 << (c - b'a');
+        }
+        return Ok(SegmentSet(bits));
+    }
+}
+
+impl std::fmt::Display for SegmentSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for c in 'a'..='h' {
+            if self.contains(c) {
+                write!(f, "{}", c)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+// Every seven segment digit has a different size and overlap with 1 and 4, whatever the
+// wiring, so those are all it takes to tell them apart.
+const SIGNATURES: [(u32, u32, u32); 10] = [
+    (6, 2, 3),
+    (2, 2, 2),
+    (5, 1, 2),
+    (5, 2, 3),
+    (4, 2, 4),
+    (5, 1, 3),
+    (6, 1, 3),
+    (3, 2, 2),
+    (7, 2, 4),
+    (6, 2, 4),
+];
+
+/// Read the number on a scrambled seven segment display, given its signal patterns and
+/// output patterns as space separated words.
+///
+/// Only works for the usual digits, but needs nothing more than a 1 and a 4 somewhere in the
+/// line, and doesn't allocate. None if it can't find those or an output isn't a digit.
+///
+/// ```
+/// let signals = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab";
+/// assert_eq!(day08::decode_seven_segment(signals, "cdfeb fcadb cdfeb cdbaf"), Some(5353));
+/// assert_eq!(day08::decode_seven_segment("ab eafb", "cdfeb"), Some(5));
+/// assert_eq!(day08::decode_seven_segment("eafb", "cdfeb"), None);
+/// ```
+pub fn decode_seven_segment(signals: &str, outputs: &str) -> Option<usize> {
+    let mut one: Option<SegmentSet> = None;
+    let mut four: Option<SegmentSet> = None;
+    for word in signals.split_whitespace().chain(outputs.split_whitespace()) {
+        match word.len() {
+            2 => one = Some(word.parse().ok()?),
+            4 => four = Some(word.parse().ok()?),
+            _ => {}
+        }
+    }
+    let (one, four) = (one?, four?);
+
+    let mut value = 0;
+    for word in outputs.split_whitespace() {
+        let set: SegmentSet = word.parse().ok()?;
+        let signature = (
+            set.len(),
+            set.intersection(one).len(),
+            set.intersection(four).len(),
+        );
+        let digit = SIGNATURES.iter().position(|s| *s == signature)?;
+        value = value * 10 + digit;
+    }
+    return Some(value);
+}
@@ -0,0 +1,217 @@
+use crate::boardlib::{Board, BoardCoordinate, BoardTraversable};
+use std::collections::VecDeque;
+
+/// Label given to cells that aren't in any basin.
+pub const NO_BASIN: u32 = 0;
+
+/// How to split a heightmap into basins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentOptions {
+    /// Cells this high or higher are walls, and belong to no basin.
+    pub wall_height: Option<usize>,
+    /// Follow each cell downhill to where it drains, instead of filling out to the walls.
+    pub flow_downhill: bool,
+}
+
+impl SegmentOptions {
+    /// Basins are whatever is fenced in by 9s, like the puzzle.
+    pub fn puzzle() -> SegmentOptions {
+        return SegmentOptions {
+            wall_height: Some(9),
+            flow_downhill: false,
+        };
+    }
+}
+
+/// What a single basin looks like.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasinStats {
+    pub label: u32,
+    pub size: usize,
+    /// The lowest cell, the first one in reading order if there's a tie.
+    pub low_point: BoardCoordinate,
+    pub low_height: usize,
+    pub max_height: usize,
+}
+
+/// Every cell's basin, and the stats for each basin.
+///
+/// Basins are labelled from 1, in the order they were found reading the map, and
+/// `stats[i]` is for label `i + 1`.
+#[derive(Debug)]
+pub struct Basins {
+    pub labels: Board<u32>,
+    pub stats: Vec<BasinStats>,
+}
+
+// Label every cell reachable from `from` through cells where `joins` holds.
+fn fill(
+    board: &Board<usize>,
+    labels: &mut [u32],
+    from: BoardCoordinate,
+    label: u32,
+    joins: impl Fn(BoardCoordinate, BoardCoordinate) -> bool,
+) {
+    let width = board.get_width();
+    let mut queue_to_visit = vec![from];
+    labels[from.x + from.y * width] = label;
+    while let Some(coord) = queue_to_visit.pop() {
+        for next in board.get_adjacent_coordinates(coord) {
+            let i = next.x + next.y * width;
+            if labels[i] == NO_BASIN && joins(coord, next) {
+                labels[i] = label;
+                queue_to_visit.push(next);
+            }
+        }
+    }
+}
+
+/// Split a heightmap into basins.
+///
+/// By default a basin is every cell that can be reached without crossing a wall. With
+/// `flow_downhill`, each cell instead runs to its lowest lower neighbour until it can't go
+/// any lower, and belongs to the basin it ends up in. Flat bottoms count as one basin, and a
+/// flat stretch that drops off somewhere drains out through that edge.
+///
+/// ```
+/// use boardlib::{Board, BoardCoordinate, BoardTraversable};
+/// use day09::{segment_basins, SegmentOptions};
+/// let heights = vec![
+///     2, 1, 9, 9, 9, 4, 3, 2, 1, 0, 3, 9, 8, 7, 8, 9, 4, 9, 2, 1, 9, 8, 5, 6, 7, 8, 9, 8, 9,
+///     2, 8, 7, 6, 7, 8, 9, 6, 7, 8, 9, 9, 8, 9, 9, 9, 6, 5, 6, 7, 8,
+/// ];
+/// let board = Board::new(10, 5, heights);
+/// let basins = segment_basins(&board, SegmentOptions::puzzle());
+/// let sizes: Vec<usize> = basins.stats.iter().map(|b| b.size).collect();
+/// assert_eq!(sizes, vec![3, 9, 14, 9]);
+/// assert_eq!(basins.stats[1].low_point, BoardCoordinate::new(9, 0));
+/// assert_eq!(basins.stats[2].max_height, 8);
+/// assert_eq!(basins.labels.get_value(BoardCoordinate::new(0, 0)), Some(1));
+/// assert_eq!(basins.labels.get_value(BoardCoordinate::new(2, 0)), Some(0));
+///
+/// // Without walls, the 9s drain into a neighbouring basin too.
+/// let options = SegmentOptions { wall_height: None, flow_downhill: true };
+/// let basins = segment_basins(&board, options);
+/// assert_eq!(basins.stats.len(), 4);
+/// assert_eq!(basins.stats.iter().map(|b| b.size).sum::<usize>(), 50);
+/// ```
+pub fn segment_basins(board: &Board<usize>, options: SegmentOptions) -> Basins {
+    let width = board.get_width();
+    let heights = board.data_copy();
+    let height_at = |c: BoardCoordinate| heights[c.x + c.y * width];
+    let is_wall = |c: BoardCoordinate| match options.wall_height {
+        Some(wall) => height_at(c) >= wall,
+        None => false,
+    };
+
+    let mut labels: Vec<u32> = vec![NO_BASIN; heights.len()];
+    let mut next_label = 1;
+    if !options.flow_downhill {
+        for coord in board.coord_iter() {
+            if labels[coord.x + coord.y * width] == NO_BASIN && !is_wall(coord) {
+                fill(board, &mut labels, coord, next_label, |_, b| !is_wall(b));
+                next_label += 1;
+            }
+        }
+    } else {
+        // Where each cell runs to, None if nowhere around it is lower.
+        let downhill = |c: BoardCoordinate| {
+            return board
+                .get_adjacent_coordinates(c)
+                .into_iter()
+                .filter(|n| !is_wall(*n) && height_at(*n) < height_at(c))
+                .min_by_key(|n| height_at(*n));
+        };
+        let flat =
+            |a: BoardCoordinate, b: BoardCoordinate| !is_wall(b) && height_at(a) == height_at(b);
+        // Cells on a flat stretch head for the nearest edge that drops lower. A stretch
+        // with no such edge is a bottom, and gets its own label.
+        let mut next: Vec<Option<BoardCoordinate>> = vec![None; heights.len()];
+        let mut seen = vec![false; heights.len()];
+        for coord in board.coord_iter() {
+            if is_wall(coord) || seen[coord.x + coord.y * width] {
+                continue;
+            }
+            let mut plateau = vec![coord];
+            seen[coord.x + coord.y * width] = true;
+            let mut i = 0;
+            while i < plateau.len() {
+                let c = plateau[i];
+                i += 1;
+                for n in board.get_adjacent_coordinates(c) {
+                    if !seen[n.x + n.y * width] && flat(c, n) {
+                        seen[n.x + n.y * width] = true;
+                        plateau.push(n);
+                    }
+                }
+            }
+            let mut queue_to_visit: VecDeque<BoardCoordinate> = VecDeque::new();
+            for c in plateau.iter() {
+                if let Some(lower) = downhill(*c) {
+                    next[c.x + c.y * width] = Some(lower);
+                    queue_to_visit.push_back(*c);
+                }
+            }
+            if queue_to_visit.is_empty() {
+                for c in plateau {
+                    labels[c.x + c.y * width] = next_label;
+                }
+                next_label += 1;
+                continue;
+            }
+            while let Some(c) = queue_to_visit.pop_front() {
+                for n in board.get_adjacent_coordinates(c) {
+                    if flat(c, n) && next[n.x + n.y * width].is_none() {
+                        next[n.x + n.y * width] = Some(c);
+                        queue_to_visit.push_back(n);
+                    }
+                }
+            }
+        }
+        // Then run everything else down to a bottom.
+        for coord in board.coord_iter() {
+            if is_wall(coord) {
+                continue;
+            }
+            let mut route = Vec::new();
+            let mut cur = coord;
+            while labels[cur.x + cur.y * width] == NO_BASIN {
+                route.push(cur);
+                cur = next[cur.x + cur.y * width].unwrap();
+            }
+            let label = labels[cur.x + cur.y * width];
+            for c in route {
+                labels[c.x + c.y * width] = label;
+            }
+        }
+    }
+
+    let mut stats: Vec<BasinStats> = (1..next_label)
+        .map(|label| BasinStats {
+            label,
+            size: 0,
+            low_point: BoardCoordinate::new(0, 0),
+            low_height: 0,
+            max_height: 0,
+        })
+        .collect();
+    for coord in board.coord_iter() {
+        let label = labels[coord.x + coord.y * width];
+        if label == NO_BASIN {
+            continue;
+        }
+        let h = height_at(coord);
+        let basin = &mut stats[(label - 1) as usize];
+        if basin.size == 0 || h < basin.low_height {
+            basin.low_point = coord;
+            basin.low_height = h;
+        }
+        basin.max_height = basin.max_height.max(h);
+        basin.size += 1;
+    }
+
+    return Basins {
+        labels: Board::new(width, board.get_height(), labels),
+        stats,
+    };
+}
@@ -6,6 +6,12 @@ use crate::boardlib::BoardTraversable;
 pub use filelib::load;
 use std::collections::HashSet;
 
+mod basins;
+pub use crate::basins::{segment_basins, BasinStats, Basins, SegmentOptions, NO_BASIN};
+
+mod render;
+pub use crate::render::{render_ansi, render_ppm};
+
 /// Get size of the board
 ///
 /// ```
@@ -217,4 +223,65 @@ mod tests {
         ];
         assert_eq!(find_basin_by_low_point(&board, 6, 4), basin4);
     }
+
+    #[test]
+    fn test_segment_matches_low_point_fill() {
+        let board = produce_board();
+        let basins = segment_basins(&board, SegmentOptions::puzzle());
+        for basin in basins.stats.iter() {
+            let filled = find_basin_by_low_point(&board, basin.low_point.x, basin.low_point.y);
+            assert_eq!(filled.len(), basin.size);
+            for coord in filled {
+                assert_eq!(basins.labels.get_value(coord), Some(basin.label));
+            }
+        }
+    }
+
+    #[test]
+    fn test_segment_flow_downhill() {
+        // Two valleys either side of a ridge, one with a flat bottom.
+        let board: boardlib::Board<usize> = boardlib::Board::new(7, 1, vec![1, 1, 2, 5, 3, 0, 4]);
+        let flowed = segment_basins(
+            &board,
+            SegmentOptions {
+                wall_height: None,
+                flow_downhill: true,
+            },
+        );
+        // The top of the ridge runs off to whichever side is lower.
+        assert_eq!(flowed.labels.data_copy(), vec![1, 1, 1, 1, 2, 2, 2]);
+        assert_eq!(flowed.stats[0].size, 4);
+        assert_eq!(flowed.stats[0].low_point, BoardCoordinate::new(0, 0));
+        assert_eq!(flowed.stats[0].max_height, 5);
+        assert_eq!(flowed.stats[1].low_point, BoardCoordinate::new(5, 0));
+        assert_eq!(flowed.stats[1].max_height, 4);
+
+        // With the ridge as a wall.
+        let walled = segment_basins(
+            &board,
+            SegmentOptions {
+                wall_height: Some(5),
+                flow_downhill: true,
+            },
+        );
+        assert_eq!(walled.labels.data_copy(), vec![1, 1, 1, 0, 2, 2, 2]);
+        assert_eq!(walled.stats[0].max_height, 2);
+    }
+
+    #[test]
+    fn test_segment_flow_downhill_shelf() {
+        // The flat 1s drain out their left edge, so they aren't a bottom of their own.
+        let board: boardlib::Board<usize> = boardlib::Board::new(5, 1, vec![0, 1, 1, 1, 2]);
+        let flowed = segment_basins(
+            &board,
+            SegmentOptions {
+                wall_height: None,
+                flow_downhill: true,
+            },
+        );
+        assert_eq!(flowed.labels.data_copy(), vec![1, 1, 1, 1, 1]);
+        assert_eq!(flowed.stats.len(), 1);
+        assert_eq!(flowed.stats[0].low_point, BoardCoordinate::new(0, 0));
+        assert_eq!(flowed.stats[0].low_height, 0);
+    }
 }
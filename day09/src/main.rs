@@ -3,6 +3,9 @@ use day09::get_board_size;
 use day09::load;
 use day09::puzzle_a;
 use day09::puzzle_b;
+use day09::{render_ansi, render_ppm, segment_basins, SegmentOptions};
+use std::env;
+use std::fs;
 
 fn main() {
     let filename = "input";
@@ -15,6 +18,23 @@ fn main() {
     let len = numbers.len();
     assert_eq!(len, width * height);
 
+    // --render draws the basins on the terminal, --ppm file writes them as an image.
+    let args: Vec<String> = env::args().collect();
+    let ppm_file = args
+        .iter()
+        .position(|a| a == "--ppm")
+        .map(|i| args[i + 1].clone());
+    if args.iter().any(|a| a == "--render") || ppm_file.is_some() {
+        let board = boardlib::Board::new(width, height, numbers);
+        let basins = segment_basins(&board, SegmentOptions::puzzle());
+        if let Some(file) = ppm_file {
+            fs::write(file, render_ppm(&board, &basins, 4)).unwrap();
+        } else {
+            println!("{}", render_ansi(&board, &basins));
+        }
+        return;
+    }
+
     let value_a = puzzle_a(numbers, width, height);
     println!("Solution to 1: {}", value_a);
 
@@ -0,0 +1,125 @@
+use crate::boardlib::{Board, BoardCoordinate};
+use crate::{Basins, NO_BASIN};
+use std::collections::HashSet;
+
+// Background colours basins cycle through on the terminal: red, green, yellow, blue,
+// magenta, cyan.
+const ANSI_BACKGROUNDS: [u32; 6] = [41, 42, 43, 44, 45, 46];
+
+// Same idea for images, a little softer.
+const PPM_COLOURS: [[u8; 3]; 6] = [
+    [220, 80, 80],
+    [80, 190, 90],
+    [220, 200, 70],
+    [80, 120, 220],
+    [190, 90, 200],
+    [70, 190, 200],
+];
+
+fn low_points(basins: &Basins) -> HashSet<BoardCoordinate> {
+    return basins.stats.iter().map(|b| b.low_point).collect();
+}
+
+/// Draw the heightmap for a terminal, each basin on its own background colour.
+///
+/// Every cell shows its height. Cells in no basin are left plain, and each basin's low
+/// point is bold white. Lines end by resetting the colour.
+///
+/// ```
+/// use boardlib::Board;
+/// use day09::{render_ansi, segment_basins, SegmentOptions};
+/// let board = Board::new(4, 1, vec![1, 0, 9, 3]);
+/// let basins = segment_basins(&board, SegmentOptions::puzzle());
+/// assert_eq!(
+///     render_ansi(&board, &basins),
+///     "\x1b[0;41m1\x1b[0;1;97;41m0\x1b[0m9\x1b[0;1;97;42m3\x1b[0m"
+/// );
+/// ```
+pub fn render_ansi(heights: &Board<usize>, basins: &Basins) -> String {
+    let lows = low_points(basins);
+    // Straight into the data, as get_value has to walk to each cell.
+    let height_values = heights.data_copy();
+    let label_values = basins.labels.data_copy();
+    let width = heights.get_width();
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for coord in heights.coord_iter() {
+        let height = height_values[coord.x + coord.y * width];
+        let label = label_values[coord.x + coord.y * width];
+        if label == NO_BASIN {
+            line.push_str(&format!("\x1b[0m{}", height));
+        } else {
+            let background = ANSI_BACKGROUNDS[(label - 1) as usize % ANSI_BACKGROUNDS.len()];
+            if lows.contains(&coord) {
+                line.push_str(&format!("\x1b[0;1;97;{}m{}", background, height));
+            } else {
+                line.push_str(&format!("\x1b[0;{}m{}", background, height));
+            }
+        }
+        if coord.x + 1 == width {
+            line.push_str("\x1b[0m");
+            lines.push(line);
+            line = String::new();
+        }
+    }
+    return lines.join("\n");
+}
+
+/// Draw the heightmap as a binary PPM image, `scale` pixels square per cell.
+///
+/// Basins get their own colour, darker the higher the cell is, cells in no basin are grey,
+/// and low points are white.
+///
+/// ```
+/// use boardlib::Board;
+/// use day09::{render_ppm, segment_basins, SegmentOptions};
+/// let board = Board::new(4, 1, vec![1, 0, 9, 3]);
+/// let basins = segment_basins(&board, SegmentOptions::puzzle());
+/// let image = render_ppm(&board, &basins, 2);
+/// let header = b"P6\n8 2\n255\n";
+/// assert!(image.starts_with(header));
+/// assert_eq!(image.len(), header.len() + 8 * 2 * 3);
+/// // The low point is white.
+/// let low = header.len() + 2 * 3;
+/// assert_eq!(&image[low..low + 3], &[255, 255, 255]);
+/// ```
+pub fn render_ppm(heights: &Board<usize>, basins: &Basins, scale: usize) -> Vec<u8> {
+    let lows = low_points(basins);
+    let height_values = heights.data_copy();
+    let label_values = basins.labels.data_copy();
+    let max_height = height_values.iter().copied().max().unwrap_or(0).max(1);
+    let width = heights.get_width();
+
+    // One row of cells at a time, then repeated to scale it up.
+    let mut image = format!(
+        "P6\n{} {}\n255\n",
+        width * scale,
+        heights.get_height() * scale
+    )
+    .into_bytes();
+    let mut row: Vec<u8> = Vec::new();
+    for coord in heights.coord_iter() {
+        let height = height_values[coord.x + coord.y * width];
+        let label = label_values[coord.x + coord.y * width];
+        // Fade from full colour at the bottom to a third of it at the top.
+        let shade = |c: u8| (c as usize * (3 * max_height - 2 * height) / (3 * max_height)) as u8;
+        let pixel: [u8; 3] = if lows.contains(&coord) {
+            [255, 255, 255]
+        } else if label == NO_BASIN {
+            [shade(160); 3]
+        } else {
+            let colour = PPM_COLOURS[(label - 1) as usize % PPM_COLOURS.len()];
+            [shade(colour[0]), shade(colour[1]), shade(colour[2])]
+        };
+        for _ in 0..scale {
+            row.extend_from_slice(&pixel);
+        }
+        if coord.x + 1 == width {
+            for _ in 0..scale {
+                image.extend_from_slice(&row);
+            }
+            row.clear();
+        }
+    }
+    return image;
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+/// An opening and closing delimiter, and what they're worth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BracketPair {
+    pub open: char,
+    pub close: char,
+    /// Points for finding `close` where it doesn't belong.
+    pub corrupted_score: u64,
+    /// Points for each `close` needed to finish off a line.
+    pub completion_score: u64,
+}
+
+impl BracketPair {
+    pub fn new(open: char, close: char, corrupted_score: u64, completion_score: u64) -> Self {
+        return BracketPair {
+            open,
+            close,
+            corrupted_score,
+            completion_score,
+        };
+    }
+}
+
+/// What's wrong with a line, if anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnosis {
+    Valid,
+    /// The char at `column` (counting chars from 0) closes the wrong thing. `expected` is
+    /// the close that would have fit, or None if there was nothing open.
+    Corrupted {
+        column: usize,
+        expected: Option<char>,
+        found: char,
+    },
+    /// Everything matches but some are left open, and `completion` closes them.
+    Incomplete {
+        completion: String,
+    },
+}
+
+/// Checks lines against a set of delimiter pairs.
+///
+/// Anything that isn't one of the delimiters is skipped over, so it can check text that has
+/// other things in it.
+#[derive(Debug, Clone)]
+pub struct BracketChecker {
+    pairs: Vec<BracketPair>,
+    // Which pair each open and close belongs to.
+    opens: HashMap<char, usize>,
+    closes: HashMap<char, usize>,
+    completion_base: u64,
+}
+
+impl BracketChecker {
+    /// A checker for the given pairs. No char can be used by more than one pair, or be both
+    /// the open and close of a pair.
+    ///
+    /// ```
+    /// use day10::{BracketChecker, BracketPair};
+    /// let quotes = BracketPair::new('|', '|', 1, 1);
+    /// assert!(BracketChecker::new(vec![quotes]).is_err());
+    /// let round = BracketPair::new('(', ')', 1, 1);
+    /// let backwards = BracketPair::new(')', '(', 1, 1);
+    /// assert!(BracketChecker::new(vec![round, backwards]).is_err());
+    /// ```
+    pub fn new(pairs: Vec<BracketPair>) -> Result<Self, String> {
+        let mut opens: HashMap<char, usize> = HashMap::new();
+        let mut closes: HashMap<char, usize> = HashMap::new();
+        for (i, pair) in pairs.iter().enumerate() {
+            for c in [pair.open, pair.close] {
+                if opens.contains_key(&c) || closes.contains_key(&c) {
+                    return Err(format!("{:?} is used by more than one delimiter", c));
+                }
+                if c == pair.open {
+                    opens.insert(c, i);
+                } else {
+                    closes.insert(c, i);
+                }
+            }
+        }
+        return Ok(BracketChecker {
+            pairs,
+            opens,
+            closes,
+            completion_base: 5,
+        });
+    }
+
+    /// The puzzle's (), [], {} and <>, with its scores.
+    pub fn puzzle() -> Self {
+        return BracketChecker::new(vec![
+            BracketPair::new('(', ')', 3, 1),
+            BracketPair::new('[', ']', 57, 2),
+            BracketPair::new('{', '}', 1197, 3),
+            BracketPair::new('<', '>', 25137, 4),
+        ])
+        .unwrap();
+    }
+
+    /// Multiply the completion score by `base` before adding each close, rather than 5.
+    pub fn with_completion_base(mut self, base: u64) -> Self {
+        self.completion_base = base;
+        return self;
+    }
+
+    pub fn pairs(&self) -> &[BracketPair] {
+        return &self.pairs;
+    }
+
+    /// Work out whether a line is valid, corrupted or incomplete.
+    ///
+    /// ```
+    /// use day10::{BracketChecker, Diagnosis};
+    /// let checker = BracketChecker::puzzle();
+    /// assert_eq!(checker.check("[<>({}){}[([])<>]]"), Diagnosis::Valid);
+    /// assert_eq!(
+    ///     checker.check("{([(<{}[<>[]}>{[]{[(<()>"),
+    ///     Diagnosis::Corrupted { column: 12, expected: Some(']'), found: '}' }
+    /// );
+    /// assert_eq!(
+    ///     checker.check("()>"),
+    ///     Diagnosis::Corrupted { column: 2, expected: None, found: '>' }
+    /// );
+    /// assert_eq!(
+    ///     checker.check("[({(<(())[]>[[{[]{<()<>>"),
+    ///     Diagnosis::Incomplete { completion: "}}]])})]".to_string() }
+    /// );
+    /// ```
+    pub fn check(&self, line: &str) -> Diagnosis {
+        let mut open: Vec<usize> = Vec::new();
+        for (column, c) in line.chars().enumerate() {
+            if let Some(pair) = self.opens.get(&c) {
+                open.push(*pair);
+            } else if let Some(pair) = self.closes.get(&c) {
+                match open.pop() {
+                    Some(expected) if expected == *pair => {}
+                    expected => {
+                        return Diagnosis::Corrupted {
+                            column,
+                            expected: expected.map(|p| self.pairs[p].close),
+                            found: c,
+                        };
+                    }
+                }
+            }
+        }
+        if open.is_empty() {
+            return Diagnosis::Valid;
+        }
+        let completion = open.iter().rev().map(|p| self.pairs[*p].close).collect();
+        return Diagnosis::Incomplete { completion };
+    }
+
+    /// Points for a corrupted line's first bad close, 0 for anything else.
+    ///
+    /// ```
+    /// use day10::BracketChecker;
+    /// let checker = BracketChecker::puzzle();
+    /// assert_eq!(checker.corrupted_score(&checker.check("<{([([[(<>()){}]>(<<{{")), 25137);
+    /// assert_eq!(checker.corrupted_score(&checker.check("(")), 0);
+    /// ```
+    pub fn corrupted_score(&self, diagnosis: &Diagnosis) -> u64 {
+        if let Diagnosis::Corrupted { found, .. } = diagnosis {
+            return self.pairs[self.closes[found]].corrupted_score;
+        }
+        return 0;
+    }
+
+    /// Score for finishing off an incomplete line, None for anything else. Gets stuck at
+    /// u64::MAX rather than overflowing for very long completions.
+    ///
+    /// ```
+    /// use day10::BracketChecker;
+    /// let checker = BracketChecker::puzzle();
+    /// assert_eq!(checker.completion_score(&checker.check("<{([{{}}[<[[[<>{}]]]>[]]")), Some(294));
+    /// let checker = checker.with_completion_base(10);
+    /// assert_eq!(checker.completion_score(&checker.check("<{([")), Some(2134));
+    /// assert_eq!(checker.completion_score(&checker.check("()")), None);
+    /// ```
+    pub fn completion_score(&self, diagnosis: &Diagnosis) -> Option<u64> {
+        if let Diagnosis::Incomplete { completion } = diagnosis {
+            let score = completion.chars().fold(0u64, |score, c| {
+                return score
+                    .saturating_mul(self.completion_base)
+                    .saturating_add(self.pairs[self.closes[&c]].completion_score);
+            });
+            return Some(score);
+        }
+        return None;
+    }
+}
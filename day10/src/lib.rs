@@ -1,110 +1,22 @@
-pub use filelib::load;
-use std::collections::HashSet;
-// chunk pairs: (), [], {}, <>
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum Chunks {
-    OpenRound,
-    CloseRound,
-    OpenSquare,
-    CloseSquare,
-    OpenCurly,
-    CloseCurly,
-    OpenTriangle,
-    CloseTriangle,
-}
-
-impl Chunks {
-    fn get_points(&self) -> Option<u64> {
-        match self {
-            Chunks::CloseRound => Some(3),
-            Chunks::CloseSquare => Some(57),
-            Chunks::CloseCurly => Some(1197),
-            Chunks::CloseTriangle => Some(25137),
-            Chunks::OpenRound => Some(1),
-            Chunks::OpenSquare => Some(2),
-            Chunks::OpenCurly => Some(3),
-            Chunks::OpenTriangle => Some(4),
-        }
-    }
-
-    fn get_matching_end_character(&self) -> Option<Chunks> {
-        match self {
-            Chunks::OpenRound => Some(Chunks::CloseRound),
-            Chunks::OpenSquare => Some(Chunks::CloseSquare),
-            Chunks::OpenCurly => Some(Chunks::CloseCurly),
-            Chunks::OpenTriangle => Some(Chunks::CloseTriangle),
-            _ => None,
-        }
-    }
-}
-
-impl std::str::FromStr for Chunks {
-    type Err = ();
+mod checker;
+mod repair;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "(" => Ok(Chunks::OpenRound),
-            ")" => Ok(Chunks::CloseRound),
-            "{" => Ok(Chunks::OpenCurly),
-            "}" => Ok(Chunks::CloseCurly),
-            "[" => Ok(Chunks::OpenSquare),
-            "]" => Ok(Chunks::CloseSquare),
-            "<" => Ok(Chunks::OpenTriangle),
-            ">" => Ok(Chunks::CloseTriangle),
-            _ => Err(()),
-        }
-    }
-}
+pub use crate::checker::{BracketChecker, BracketPair, Diagnosis};
+pub use crate::repair::{Edit, Repair};
+pub use filelib::load;
 
 fn line_is_corrupted(line: &str) -> (bool, u64) {
-    let mut unclosed_sets: Vec<Chunks> = Vec::new();
-    let mut open_chunk_set: HashSet<Chunks> = HashSet::new();
-    open_chunk_set.insert(Chunks::OpenRound);
-    open_chunk_set.insert(Chunks::OpenSquare);
-    open_chunk_set.insert(Chunks::OpenCurly);
-    open_chunk_set.insert(Chunks::OpenTriangle);
-
-    for x in line.chars() {
-        let cur_chunk: Chunks = x.to_string().parse::<Chunks>().unwrap();
-        if open_chunk_set.contains(&cur_chunk) {
-            unclosed_sets.push(cur_chunk);
-        } else {
-            if let Some(expected_opening) = unclosed_sets.pop() {
-                if cur_chunk != expected_opening.get_matching_end_character().unwrap() {
-                    return (true, cur_chunk.get_points().unwrap());
-                }
-            }
-        }
-    }
-    return (false, 0);
+    let checker = BracketChecker::puzzle();
+    let diagnosis = checker.check(line);
+    return match diagnosis {
+        Diagnosis::Corrupted { .. } => (true, checker.corrupted_score(&diagnosis)),
+        _ => (false, 0),
+    };
 }
 
 fn complete_line(line: &str) -> u64 {
-    let mut unclosed_sets: Vec<Chunks> = Vec::new();
-    let mut open_chunk_set: HashSet<Chunks> = HashSet::new();
-    open_chunk_set.insert(Chunks::OpenRound);
-    open_chunk_set.insert(Chunks::OpenSquare);
-    open_chunk_set.insert(Chunks::OpenCurly);
-    open_chunk_set.insert(Chunks::OpenTriangle);
-
-    for x in line.chars() {
-        let cur_chunk: Chunks = x.to_string().parse::<Chunks>().unwrap();
-        if open_chunk_set.contains(&cur_chunk) {
-            unclosed_sets.push(cur_chunk);
-        } else {
-            // This isn't corrupt, so I can just pop it, its fine.
-            unclosed_sets.pop();
-        }
-    }
-
-    let mut score: u64 = 0;
-    while let Some(stack_value) = unclosed_sets.pop() {
-        score *= 5;
-        score += stack_value.get_points().unwrap();
-    }
-
-    return score;
+    let checker = BracketChecker::puzzle();
+    return checker.completion_score(&checker.check(line)).unwrap_or(0);
 }
 
 /// Check if each line is corrupted, and add together thep oints of the ones that are corrupted
@@ -161,7 +73,92 @@ mod tests {
         assert_eq!(complete_line("[({(<(())[]>[[{[]{<()<>>"), 288957);
         assert_eq!(complete_line("[(()[<>])]({[<{<<[]>>("), 5566);
         assert_eq!(complete_line("(((({<>}<{<{<>}{[]{[]{}"), 1480781);
-        assert_eq!(complete_line("<[[]]>}<{[{[{[]{()[[[]"), 995444);
+        assert_eq!(complete_line("{<[[]]>}<{[{[{[]{()[[[]"), 995444);
         assert_eq!(complete_line("<{([{{}}[<[[[<>{}]]]>[]]"), 294);
     }
+
+    #[test]
+    fn test_repair_example() {
+        let checker = BracketChecker::puzzle();
+        let lines = [
+            "[({(<(())[]>[[{[]{<()<>>",
+            "{([(<{}[<>[]}>{[]{[(<()>",
+            "[[<[([]))<([[{}[[()]]]",
+            "<{([([[(<>()){}]>(<<{{",
+            "<{([{{}}[<[[[<>{}]]]>[]]",
+        ];
+        for line in lines {
+            let repair = checker.repair(line);
+            assert_eq!(checker.check(&repair.repaired), Diagnosis::Valid);
+            if let Diagnosis::Incomplete { completion } = checker.check(line) {
+                // Closing everything off always works, so it can't take more than that.
+                assert!(repair.edits.len() <= completion.len());
+            }
+        }
+        assert_eq!(checker.repair("{([(<{}[<>[]}>{[]{[(<()>").edits.len(), 5);
+        assert_eq!(checker.repair("[[<[([]))<([[{}[[()]]]").edits.len(), 4);
+    }
+
+    // Every line one insert, delete or substitution away, including the line itself.
+    fn one_edit_away(line: &str, alphabet: &[char]) -> Vec<String> {
+        let chars: Vec<char> = line.chars().collect();
+        let mut lines = Vec::new();
+        for i in 0..=chars.len() {
+            for c in alphabet {
+                let mut inserted = chars.clone();
+                inserted.insert(i, *c);
+                lines.push(inserted.iter().collect());
+                if i < chars.len() {
+                    let mut substituted = chars.clone();
+                    substituted[i] = *c;
+                    lines.push(substituted.iter().collect());
+                }
+            }
+            if i < chars.len() {
+                let mut deleted = chars.clone();
+                deleted.remove(i);
+                lines.push(deleted.iter().collect());
+            }
+        }
+        return lines;
+    }
+
+    #[test]
+    fn test_repair_is_minimal() {
+        let checker = BracketChecker::new(vec![
+            BracketPair::new('(', ')', 1, 1),
+            BracketPair::new('[', ']', 1, 1),
+        ])
+        .unwrap();
+        let alphabet = ['(', ')', '[', ']'];
+        let mut lines = vec![String::new()];
+        for _ in 0..5 {
+            lines = lines
+                .iter()
+                .flat_map(|l| alphabet.iter().map(move |c| format!("{}{}", l, c)))
+                .collect();
+            for line in lines.iter() {
+                let repair = checker.repair(line);
+                assert_eq!(checker.check(&repair.repaired), Diagnosis::Valid);
+                // Look for anything valid with fewer edits.
+                let mut reachable = vec![line.clone()];
+                for _ in 1..repair.edits.len() {
+                    reachable = reachable
+                        .iter()
+                        .flat_map(|l| one_edit_away(l, &alphabet))
+                        .collect();
+                }
+                if !repair.edits.is_empty() {
+                    assert!(
+                        !reachable
+                            .iter()
+                            .any(|l| checker.check(l) == Diagnosis::Valid),
+                        "{} can be fixed with fewer edits than {:?}",
+                        line,
+                        repair.edits
+                    );
+                }
+            }
+        }
+    }
 }
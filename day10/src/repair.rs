@@ -0,0 +1,230 @@
+use crate::BracketChecker;
+
+/// One change made to a line. Columns count chars from 0 in the original line, and an
+/// insert goes just before the char at its column (or on the end if it's the line length).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Insert {
+        column: usize,
+        insert: char,
+    },
+    Delete {
+        column: usize,
+        found: char,
+    },
+    Substitute {
+        column: usize,
+        found: char,
+        replace: char,
+    },
+}
+
+/// A line fixed up with as few edits as possible, and the edits that did it, in column order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repair {
+    pub repaired: String,
+    pub edits: Vec<Edit>,
+}
+
+// A delimiter from the line: its column, which pair it belongs to, and whether it opens.
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    column: usize,
+    pair: usize,
+    open: bool,
+}
+
+// How a stretch of tokens was fixed, from its first token's point of view.
+#[derive(Debug, Clone, Copy)]
+enum Choice {
+    Empty,
+    // The first token has no partner, so it gets one put at the end of the stretch if it
+    // opens, or gets deleted if it closes.
+    Unpaired,
+    // The first token is matched with the token at this index, and `cost` of the two need
+    // swapping for that to work.
+    Paired { with: usize, cost: u32 },
+}
+
+struct Solver<'a> {
+    checker: &'a BracketChecker,
+    tokens: Vec<Token>,
+    // Where an insert at the end of tokens[..j] goes in the line.
+    ends: Vec<usize>,
+    // choices[i][j] is the best way to fix tokens[i..j].
+    choices: Vec<Vec<Choice>>,
+    inserts: Vec<Vec<char>>,
+    changes: Vec<Option<Option<char>>>,
+}
+
+impl<'a> Solver<'a> {
+    // How many swaps it takes to make a and b a matching pair, and what to swap them to.
+    fn pair_cost(&self, a: Token, b: Token) -> (u32, usize) {
+        if a.open && !b.open && a.pair == b.pair {
+            return (0, a.pair);
+        }
+        if a.open {
+            return (1, a.pair);
+        }
+        if !b.open {
+            return (1, b.pair);
+        }
+        return (2, 0);
+    }
+
+    fn solve(&mut self) {
+        let n = self.tokens.len();
+        // Edits, then substitutions among them. Ties go to fewer substitutions, so lines
+        // that are just incomplete get completed rather than having opens flipped to closes.
+        let mut costs = vec![vec![(0u32, 0u32); n + 1]; n + 1];
+        self.choices = vec![vec![Choice::Empty; n + 1]; n + 1];
+        for length in 1..=n {
+            for i in 0..=(n - length) {
+                let j = i + length;
+                let mut best = (costs[i + 1][j].0 + 1, costs[i + 1][j].1);
+                let mut choice = Choice::Unpaired;
+                for k in (i + 1)..j {
+                    let (cost, _) = self.pair_cost(self.tokens[i], self.tokens[k]);
+                    let (inside, after) = (costs[i + 1][k], costs[k + 1][j]);
+                    let total = (cost + inside.0 + after.0, cost + inside.1 + after.1);
+                    if total < best {
+                        best = total;
+                        choice = Choice::Paired { with: k, cost };
+                    }
+                }
+                costs[i][j] = best;
+                self.choices[i][j] = choice;
+            }
+        }
+    }
+
+    // Write out the edits for tokens[i..j]. Anything inserted at the end of a stretch has
+    // to go after whatever the stretches inside it inserted there, so this works inside out.
+    fn collect(&mut self, i: usize, j: usize) {
+        match self.choices[i][j] {
+            Choice::Empty => {}
+            Choice::Unpaired => {
+                self.collect(i + 1, j);
+                let token = self.tokens[i];
+                if token.open {
+                    let close = self.checker.pairs()[token.pair].close;
+                    self.inserts[self.ends[j]].push(close);
+                } else {
+                    self.changes[token.column] = Some(None);
+                }
+            }
+            Choice::Paired { with, cost } => {
+                self.collect(i + 1, with);
+                self.collect(with + 1, j);
+                if cost > 0 {
+                    let (a, b) = (self.tokens[i], self.tokens[with]);
+                    let target = self.pair_cost(a, b).1;
+                    let pair = self.checker.pairs()[target];
+                    if !a.open || a.pair != target {
+                        self.changes[a.column] = Some(Some(pair.open));
+                    }
+                    if b.open || b.pair != target {
+                        self.changes[b.column] = Some(Some(pair.close));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl BracketChecker {
+    /// Fix a line with as few inserts, deletes and substitutions as possible.
+    ///
+    /// Opens left with nothing to close them get their close added at the end of the
+    /// stretch they're in, like completing the line, and stray closes are deleted. Between
+    /// fixes with the same number of edits, the one with fewest substitutions wins. Anything that
+    /// isn't a delimiter is left where it is. This is cubic in the number of delimiters, so
+    /// it's for lines rather than whole files.
+    ///
+    /// ```
+    /// use day10::{BracketChecker, Diagnosis, Edit};
+    /// let checker = BracketChecker::puzzle();
+    ///
+    /// // Flipping an open to a close can fix two at once.
+    /// let repair = checker.repair("[({");
+    /// assert_eq!(repair.repaired, "[()]");
+    /// assert_eq!(
+    ///     repair.edits,
+    ///     vec![
+    ///         Edit::Substitute { column: 2, found: '{', replace: ')' },
+    ///         Edit::Insert { column: 3, insert: ']' },
+    ///     ]
+    /// );
+    ///
+    /// let repair = checker.repair("[(]");
+    /// assert_eq!(repair.repaired, "[()]");
+    /// assert_eq!(repair.edits, vec![Edit::Insert { column: 2, insert: ')' }]);
+    ///
+    /// let repair = checker.repair("a{b>c");
+    /// assert_eq!(repair.repaired, "a{b}c");
+    /// assert_eq!(
+    ///     repair.edits,
+    ///     vec![Edit::Substitute { column: 3, found: '>', replace: '}' }]
+    /// );
+    ///
+    /// let repair = checker.repair(")(");
+    /// assert_eq!(repair.repaired, "()");
+    /// assert_eq!(repair.edits.len(), 2);
+    /// assert_eq!(checker.check(&repair.repaired), Diagnosis::Valid);
+    /// ```
+    pub fn repair(&self, line: &str) -> Repair {
+        let chars: Vec<char> = line.chars().collect();
+        let mut tokens: Vec<Token> = Vec::new();
+        for (column, c) in chars.iter().enumerate() {
+            for (pair, p) in self.pairs().iter().enumerate() {
+                if *c == p.open || *c == p.close {
+                    let open = *c == p.open;
+                    tokens.push(Token { column, pair, open });
+                }
+            }
+        }
+        let mut ends: Vec<usize> = tokens.iter().map(|t| t.column).collect();
+        ends.push(chars.len());
+
+        let n = tokens.len();
+        let mut solver = Solver {
+            checker: self,
+            tokens,
+            ends,
+            choices: Vec::new(),
+            inserts: vec![Vec::new(); chars.len() + 1],
+            changes: vec![None; chars.len()],
+        };
+        solver.solve();
+        solver.collect(0, n);
+
+        let mut repaired = String::new();
+        let mut edits: Vec<Edit> = Vec::new();
+        for column in 0..=chars.len() {
+            for insert in solver.inserts[column].iter() {
+                repaired.push(*insert);
+                edits.push(Edit::Insert {
+                    column,
+                    insert: *insert,
+                });
+            }
+            if column == chars.len() {
+                break;
+            }
+            let found = chars[column];
+            match solver.changes[column] {
+                None => repaired.push(found),
+                Some(None) => edits.push(Edit::Delete { column, found }),
+                Some(Some(replace)) => {
+                    repaired.push(replace);
+                    edits.push(Edit::Substitute {
+                        column,
+                        found,
+                        replace,
+                    });
+                }
+            }
+        }
+        return Repair { repaired, edits };
+    }
+}
@@ -0,0 +1,196 @@
+use crate::boardlib::Board;
+
+/// A grid of octopuses of any size, stepped one flash at a time.
+///
+/// Each step every octopus gains one energy, anything over the threshold flashes and gives
+/// one energy to all eight neighbours, which can make them flash too. Nothing flashes more
+/// than once a step, and everything that flashed drops back to the reset energy at the end.
+///
+/// Flashes are passed along from a worklist, so a step only looks at each octopus once plus
+/// once per neighbouring flash, however far the cascade spreads.
+#[derive(Debug, Clone)]
+pub struct OctopusSimulator {
+    width: usize,
+    height: usize,
+    energies: Vec<u32>,
+    threshold: u32,
+    reset: u32,
+    flash_history: Vec<usize>,
+    // Scratch space for a step, kept around so big grids don't reallocate every time.
+    flashed: Vec<bool>,
+    worklist: Vec<usize>,
+}
+
+impl OctopusSimulator {
+    /// Octopuses in reading order, flashing above 9 and resetting to 0 like the puzzle.
+    ///
+    /// ```
+    /// use day11::OctopusSimulator;
+    /// assert!(OctopusSimulator::new(3, 2, vec![0; 6]).is_ok());
+    /// assert!(OctopusSimulator::new(3, 2, vec![0; 5]).is_err());
+    /// ```
+    pub fn new(width: usize, height: usize, energies: Vec<u32>) -> Result<Self, String> {
+        if energies.len() != width * height {
+            return Err(format!(
+                "Expected {} energies for a {}x{} grid, got {}",
+                width * height,
+                width,
+                height,
+                energies.len()
+            ));
+        }
+        let cells = energies.len();
+        return Ok(OctopusSimulator {
+            width,
+            height,
+            energies,
+            threshold: 9,
+            reset: 0,
+            flash_history: Vec::new(),
+            flashed: vec![false; cells],
+            worklist: Vec::new(),
+        });
+    }
+
+    /// Start from the energies on a board.
+    pub fn from_board(board: &Board<u32>) -> Self {
+        return OctopusSimulator::new(board.get_width(), board.get_height(), board.data_copy())
+            .unwrap();
+    }
+
+    /// Flash once energy goes above `threshold` rather than 9.
+    pub fn with_threshold(mut self, threshold: u32) -> Self {
+        self.threshold = threshold;
+        return self;
+    }
+
+    /// Drop back to `reset` energy after flashing rather than 0.
+    pub fn with_reset(mut self, reset: u32) -> Self {
+        self.reset = reset;
+        return self;
+    }
+
+    pub fn get_width(&self) -> usize {
+        return self.width;
+    }
+
+    pub fn get_height(&self) -> usize {
+        return self.height;
+    }
+
+    /// Current energies in reading order.
+    pub fn energies(&self) -> &[u32] {
+        return &self.energies;
+    }
+
+    /// How many octopuses flashed on each step so far, first step first.
+    pub fn flash_history(&self) -> &[usize] {
+        return &self.flash_history;
+    }
+
+    pub fn steps_taken(&self) -> usize {
+        return self.flash_history.len();
+    }
+
+    pub fn total_flashes(&self) -> usize {
+        return self.flash_history.iter().sum();
+    }
+
+    pub fn to_board(&self) -> Board<u32> {
+        return Board::new(self.width, self.height, self.energies.clone());
+    }
+
+    // Give one energy to the octopus at `i`, queueing it up if that makes it flash.
+    fn charge(&mut self, i: usize) {
+        self.energies[i] = self.energies[i].saturating_add(1);
+        if self.energies[i] > self.threshold && !self.flashed[i] {
+            self.flashed[i] = true;
+            self.worklist.push(i);
+        }
+    }
+
+    /// Take one step, returning how many octopuses flashed.
+    ///
+    /// ```
+    /// use day11::OctopusSimulator;
+    /// let mut sim = OctopusSimulator::new(5, 5, vec![
+    ///     1, 1, 1, 1, 1,
+    ///     1, 9, 9, 9, 1,
+    ///     1, 9, 1, 9, 1,
+    ///     1, 9, 9, 9, 1,
+    ///     1, 1, 1, 1, 1,
+    /// ]).unwrap();
+    /// assert_eq!(sim.step(), 9);
+    /// assert_eq!(sim.energies(), &[
+    ///     3, 4, 5, 4, 3,
+    ///     4, 0, 0, 0, 4,
+    ///     5, 0, 0, 0, 5,
+    ///     4, 0, 0, 0, 4,
+    ///     3, 4, 5, 4, 3,
+    /// ]);
+    /// assert_eq!(sim.step(), 0);
+    /// assert_eq!(sim.flash_history(), &[9, 0]);
+    /// ```
+    pub fn step(&mut self) -> usize {
+        for i in 0..self.energies.len() {
+            self.charge(i);
+        }
+
+        let mut flashes = Vec::new();
+        while let Some(i) = self.worklist.pop() {
+            flashes.push(i);
+            let (x, y) = (i % self.width, i / self.width);
+            for ny in y.saturating_sub(1)..=(y + 1).min(self.height - 1) {
+                for nx in x.saturating_sub(1)..=(x + 1).min(self.width - 1) {
+                    if nx != x || ny != y {
+                        self.charge(nx + ny * self.width);
+                    }
+                }
+            }
+        }
+
+        for i in flashes.iter() {
+            self.energies[*i] = self.reset;
+            self.flashed[*i] = false;
+        }
+        self.flash_history.push(flashes.len());
+        return flashes.len();
+    }
+
+    /// Take `steps` steps, returning how many flashes there were between them.
+    pub fn run(&mut self, steps: usize) -> usize {
+        return (0..steps).map(|_| self.step()).sum();
+    }
+
+    /// The first step on which every octopus flashed, counting from 1.
+    ///
+    /// Looks back over the steps already taken, then keeps stepping until they sync or
+    /// `max_steps` have been taken in total, giving None if they never did.
+    ///
+    /// ```
+    /// use day11::OctopusSimulator;
+    /// let mut sim = OctopusSimulator::new(2, 2, vec![7, 8, 8, 9]).unwrap();
+    /// assert_eq!(sim.first_synchronised_step(100), Some(1));
+    ///
+    /// // A higher reset means octopuses that flash keep flashing.
+    /// let mut sim = OctopusSimulator::new(2, 1, vec![9, 0]).unwrap().with_reset(9);
+    /// assert_eq!(sim.first_synchronised_step(100), Some(5));
+    /// assert_eq!(sim.flash_history()[..3], [1, 1, 1]);
+    ///
+    /// let mut sim = OctopusSimulator::new(3, 1, vec![0, 0, 5]).unwrap().with_threshold(20);
+    /// assert_eq!(sim.first_synchronised_step(10), None);
+    /// assert_eq!(sim.steps_taken(), 10);
+    /// ```
+    pub fn first_synchronised_step(&mut self, max_steps: usize) -> Option<usize> {
+        let everyone = self.energies.len();
+        if let Some(i) = self.flash_history.iter().position(|f| *f == everyone) {
+            return Some(i + 1);
+        }
+        while self.steps_taken() < max_steps {
+            if self.step() == everyone {
+                return Some(self.steps_taken());
+            }
+        }
+        return None;
+    }
+}
@@ -0,0 +1,325 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::LiteGraph;
+
+/// A cave, as an index into its `CaveSystem`.
+pub type CaveId = usize;
+
+/// A `LiteGraph` with every cave swapped for a small integer id, so walking it never has
+/// to touch a string.
+#[derive(Debug, Clone)]
+pub struct CaveSystem {
+    names: Vec<String>,
+    ids: FxHashMap<String, CaveId>,
+    large: Vec<bool>,
+    adjacencies: Vec<Vec<CaveId>>,
+}
+
+/// Rules for which caves a path may go into.
+///
+/// Large caves can always be visited again, and the start can never be gone back to.
+/// Small caves can be visited `max_visits` times, and up to `allowed_doubles` of them can
+/// be visited once more than that.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisitPolicy {
+    pub max_visits: usize,
+    pub allowed_doubles: usize,
+    /// Caves that can't be visited at all.
+    pub forbidden: FxHashSet<CaveId>,
+}
+
+impl VisitPolicy {
+    /// Every small cave at most once, like the first puzzle.
+    pub fn once() -> VisitPolicy {
+        return VisitPolicy {
+            max_visits: 1,
+            allowed_doubles: 0,
+            forbidden: FxHashSet::default(),
+        };
+    }
+
+    /// Every small cave once, except for a single one that can be visited twice, like
+    /// the second puzzle.
+    pub fn one_double() -> VisitPolicy {
+        return VisitPolicy {
+            max_visits: 1,
+            allowed_doubles: 1,
+            forbidden: FxHashSet::default(),
+        };
+    }
+}
+
+// How often each cave has been visited along the current path.
+#[derive(Debug, Clone)]
+struct Visits<'a> {
+    caves: &'a CaveSystem,
+    policy: &'a VisitPolicy,
+    start: CaveId,
+    counts: Vec<usize>,
+    doubles_used: usize,
+}
+
+impl<'a> Visits<'a> {
+    fn new(caves: &'a CaveSystem, policy: &'a VisitPolicy, start: CaveId) -> Self {
+        let mut visits = Self {
+            caves,
+            policy,
+            start,
+            counts: vec![0; caves.len()],
+            doubles_used: 0,
+        };
+        visits.counts[start] = 1;
+        return visits;
+    }
+
+    fn can_enter(&self, cave: CaveId) -> bool {
+        if cave == self.start || self.policy.forbidden.contains(&cave) {
+            return false;
+        }
+        if self.caves.is_large(cave) {
+            return true;
+        }
+        let count = self.counts[cave];
+        return count < self.policy.max_visits
+            || (count == self.policy.max_visits
+                && self.doubles_used < self.policy.allowed_doubles);
+    }
+
+    fn enter(&mut self, cave: CaveId) {
+        if !self.caves.is_large(cave) && self.counts[cave] == self.policy.max_visits {
+            self.doubles_used += 1;
+        }
+        self.counts[cave] += 1;
+    }
+
+    fn leave(&mut self, cave: CaveId) {
+        self.counts[cave] -= 1;
+        if !self.caves.is_large(cave) && self.counts[cave] == self.policy.max_visits {
+            self.doubles_used -= 1;
+        }
+    }
+}
+
+impl CaveSystem {
+    /// Give every cave in the graph an id, in name order.
+    pub fn new(graph: &LiteGraph) -> CaveSystem {
+        let mut names: Vec<String> = graph.caves().cloned().collect();
+        names.sort();
+        let ids: FxHashMap<String, CaveId> = names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (name.clone(), id))
+            .collect();
+        let large = names.iter().map(|name| graph.is_large(name)).collect();
+        let adjacencies = names
+            .iter()
+            .map(|name| graph.adj(name).map(|other| ids[other]).collect())
+            .collect();
+        return CaveSystem {
+            names,
+            ids,
+            large,
+            adjacencies,
+        };
+    }
+
+    /// How many caves there are.
+    pub fn len(&self) -> usize {
+        return self.names.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.names.is_empty();
+    }
+
+    pub fn id(&self, name: &str) -> Option<CaveId> {
+        return self.ids.get(name).copied();
+    }
+
+    pub fn name(&self, cave: CaveId) -> &str {
+        return &self.names[cave];
+    }
+
+    pub fn is_large(&self, cave: CaveId) -> bool {
+        return self.large[cave];
+    }
+
+    pub fn neighbours(&self, cave: CaveId) -> &Vec<CaveId> {
+        return &self.adjacencies[cave];
+    }
+
+    /// Write a path out the way the puzzle does, `start,A,b,end`.
+    pub fn format_path(&self, path: &[CaveId]) -> String {
+        let names: Vec<&str> = path.iter().map(|cave| self.name(*cave)).collect();
+        return names.join(",");
+    }
+
+    // Two large caves next to each other would let a path bounce between them forever.
+    // The start is never gone back to and the end finishes the path, so they don't count.
+    pub(crate) fn check_large_caves(&self, start: CaveId, end: CaveId) -> Result<(), String> {
+        let bounces = |cave: CaveId| self.is_large(cave) && cave != start && cave != end;
+        for cave in 0..self.len() {
+            if bounces(cave) && self.neighbours(cave).iter().any(|n| bounces(*n)) {
+                return Err(format!(
+                    "{} leads straight to another large cave, so there are infinitely many paths",
+                    self.name(cave)
+                ));
+            }
+        }
+        return Ok(());
+    }
+
+    fn count_from(&self, cave: CaveId, end: CaveId, visits: &mut Visits) -> usize {
+        if cave == end {
+            return 1;
+        }
+        let mut total = 0;
+        for next in self.neighbours(cave).iter() {
+            if visits.can_enter(*next) {
+                visits.enter(*next);
+                total += self.count_from(*next, end, visits);
+                visits.leave(*next);
+            }
+        }
+        return total;
+    }
+
+    /// Count the paths from start to end, without building any of them.
+    ///
+    /// Two large caves next to each other would let a path bounce between them forever,
+    /// so systems like that are an Err.
+    ///
+    /// ```
+    /// use day12::{CaveSystem, LiteGraph, VisitPolicy};
+    /// let input = vec!["start-A".to_string(), "start-b".to_string(), "A-c".to_string(),
+    ///     "A-b".to_string(), "b-d".to_string(), "A-end".to_string(), "b-end".to_string()];
+    /// let caves = CaveSystem::new(&LiteGraph::from_lines(&input));
+    /// let (start, end) = (caves.id("start").unwrap(), caves.id("end").unwrap());
+    /// assert_eq!(caves.count_paths(start, end, &VisitPolicy::once()), Ok(10));
+    /// assert_eq!(caves.count_paths(start, end, &VisitPolicy::one_double()), Ok(36));
+    ///
+    /// // Keep out of c, then let one cave be visited twice.
+    /// let mut policy = VisitPolicy::once();
+    /// policy.forbidden.insert(caves.id("c").unwrap());
+    /// assert_eq!(caves.count_paths(start, end, &policy), Ok(5));
+    /// policy.allowed_doubles = 1;
+    /// assert_eq!(caves.count_paths(start, end, &policy), Ok(13));
+    ///
+    /// // Any cave can be the start or end.
+    /// let (b, d) = (caves.id("b").unwrap(), caves.id("d").unwrap());
+    /// assert_eq!(caves.count_paths(d, b, &VisitPolicy::once()), Ok(1));
+    ///
+    /// let input = vec!["start-A".to_string(), "A-B".to_string(), "B-end".to_string()];
+    /// let caves = CaveSystem::new(&LiteGraph::from_lines(&input));
+    /// let (start, end) = (caves.id("start").unwrap(), caves.id("end").unwrap());
+    /// assert!(caves.count_paths(start, end, &VisitPolicy::once()).is_err());
+    /// ```
+    pub fn count_paths(
+        &self,
+        start: CaveId,
+        end: CaveId,
+        policy: &VisitPolicy,
+    ) -> Result<usize, String> {
+        self.check_large_caves(start, end)?;
+        let mut visits = Visits::new(self, policy, start);
+        return Ok(self.count_from(start, end, &mut visits));
+    }
+
+    /// Every path from start to end, one at a time. An Err if two large caves are next to
+    /// each other, like `count_paths`.
+    ///
+    /// ```
+    /// use day12::{CaveSystem, LiteGraph, VisitPolicy};
+    /// let input = vec!["start-A".to_string(), "start-b".to_string(), "A-c".to_string(),
+    ///     "A-b".to_string(), "b-d".to_string(), "A-end".to_string(), "b-end".to_string()];
+    /// let caves = CaveSystem::new(&LiteGraph::from_lines(&input));
+    /// let (start, end) = (caves.id("start").unwrap(), caves.id("end").unwrap());
+    /// let mut paths: Vec<String> = caves
+    ///     .paths(start, end, &VisitPolicy::once())
+    ///     .unwrap()
+    ///     .map(|p| caves.format_path(&p))
+    ///     .collect();
+    /// paths.sort();
+    /// assert_eq!(paths, vec![
+    ///     "start,A,b,A,c,A,end",
+    ///     "start,A,b,A,end",
+    ///     "start,A,b,end",
+    ///     "start,A,c,A,b,A,end",
+    ///     "start,A,c,A,b,end",
+    ///     "start,A,c,A,end",
+    ///     "start,A,end",
+    ///     "start,b,A,c,A,end",
+    ///     "start,b,A,end",
+    ///     "start,b,end",
+    /// ]);
+    /// ```
+    pub fn paths<'a>(
+        &'a self,
+        start: CaveId,
+        end: CaveId,
+        policy: &'a VisitPolicy,
+    ) -> Result<Paths<'a>, String> {
+        self.check_large_caves(start, end)?;
+        return Ok(Paths {
+            end,
+            visits: Visits::new(self, policy, start),
+            path: vec![start],
+            next_edge: vec![0],
+            at_start: start == end,
+        });
+    }
+}
+
+/// Iterator over the paths through a `CaveSystem`, from `CaveSystem::paths`.
+///
+/// It walks depth first with its own stack, so only the current path is ever held.
+#[derive(Debug, Clone)]
+pub struct Paths<'a> {
+    end: CaveId,
+    visits: Visits<'a>,
+    path: Vec<CaveId>,
+    // Which neighbour to try next, for each cave on the path.
+    next_edge: Vec<usize>,
+    // Start and end are the same cave, so the path of just that cave is due.
+    at_start: bool,
+}
+
+impl<'a> Iterator for Paths<'a> {
+    type Item = Vec<CaveId>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.at_start {
+            self.at_start = false;
+            let path = self.path.clone();
+            self.path.clear();
+            return Some(path);
+        }
+        let caves = self.visits.caves;
+        while let Some(cave) = self.path.last().copied() {
+            let edge = self.next_edge.last_mut().unwrap();
+            if *edge >= caves.neighbours(cave).len() {
+                // Tried everything from here, back up.
+                self.path.pop();
+                self.next_edge.pop();
+                if !self.path.is_empty() {
+                    self.visits.leave(cave);
+                }
+                continue;
+            }
+            let next = caves.neighbours(cave)[*edge];
+            *edge += 1;
+            if !self.visits.can_enter(next) {
+                continue;
+            }
+            if next == self.end {
+                let mut path = self.path.clone();
+                path.push(next);
+                return Some(path);
+            }
+            self.visits.enter(next);
+            self.path.push(next);
+            self.next_edge.push(0);
+        }
+        return None;
+    }
+}
@@ -0,0 +1,83 @@
+use rustc_hash::FxHashMap;
+use std::fmt::Write;
+
+use crate::LiteGraph;
+
+// Quote a cave name for dot, in case it has anything odd in it.
+fn quote(name: &str) -> String {
+    return format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""));
+}
+
+impl LiteGraph {
+    /// Write the cave system out as a Graphviz dot graph.
+    ///
+    /// Start and end are filled green and red, large caves are boxes and small caves are
+    /// circles. If a path (as cave names) is given, its tunnels are drawn thick and red,
+    /// labelled with how many times it goes through them if that's more than once.
+    ///
+    /// ```
+    /// use day12::LiteGraph;
+    /// let input = vec!["start-A".to_string(), "A-b".to_string(), "A-end".to_string()];
+    /// let graph = LiteGraph::from_lines(&input);
+    /// let dot = graph.to_dot(Some(&["start", "A", "b", "A", "end"]));
+    /// assert!(dot.starts_with("graph caves {"));
+    /// assert!(dot.contains("\"start\" [shape=doublecircle, style=filled, fillcolor=palegreen];"));
+    /// assert!(dot.contains("\"A\" [shape=box, style=bold];"));
+    /// assert!(dot.contains("\"b\" [shape=circle];"));
+    /// assert!(dot.contains("\"A\" -- \"b\" [color=red, penwidth=3, label=\"2\"];"));
+    /// assert!(dot.contains("\"A\" -- \"end\" [color=red, penwidth=3];"));
+    ///
+    /// let plain = graph.to_dot(None);
+    /// assert!(plain.contains("\"A\" -- \"b\";"));
+    /// ```
+    pub fn to_dot(&self, path: Option<&[&str]>) -> String {
+        // How many times the path goes through each tunnel, either way.
+        let mut highlighted: FxHashMap<(&str, &str), usize> = FxHashMap::default();
+        if let Some(path) = path {
+            for w in path.windows(2) {
+                let key = if w[0] <= w[1] {
+                    (w[0], w[1])
+                } else {
+                    (w[1], w[0])
+                };
+                *highlighted.entry(key).or_default() += 1;
+            }
+        }
+
+        let mut names: Vec<&String> = self.caves().collect();
+        names.sort();
+
+        let mut dot = String::new();
+        writeln!(dot, "graph caves {{").unwrap();
+        for name in names.iter() {
+            let style = if self.is_start(name) {
+                "shape=doublecircle, style=filled, fillcolor=palegreen"
+            } else if self.is_end(name) {
+                "shape=doublecircle, style=filled, fillcolor=salmon"
+            } else if self.is_large(name) {
+                "shape=box, style=bold"
+            } else {
+                "shape=circle"
+            };
+            writeln!(dot, "  {} [{}];", quote(name), style).unwrap();
+        }
+
+        for a in names.iter() {
+            let mut others: Vec<&String> = self.adj(a).filter(|b| a <= b).collect();
+            others.sort();
+            others.dedup();
+            for b in others {
+                let edge = format!("  {} -- {}", quote(a), quote(b));
+                match highlighted.get(&(a.as_str(), b.as_str())) {
+                    Some(1) => writeln!(dot, "{} [color=red, penwidth=3];", edge).unwrap(),
+                    Some(n) => {
+                        writeln!(dot, "{} [color=red, penwidth=3, label=\"{}\"];", edge, n).unwrap()
+                    }
+                    None => writeln!(dot, "{};", edge).unwrap(),
+                }
+            }
+        }
+        writeln!(dot, "}}").unwrap();
+        return dot;
+    }
+}
@@ -1,22 +1,44 @@
-use rustc_hash::{FxHashMap, FxHashSet};
+use rustc_hash::FxHashMap;
 
 pub use filelib::load_no_blanks;
 
+mod caves;
+pub use crate::caves::{CaveId, CaveSystem, Paths, VisitPolicy};
+
+mod memo;
+pub use crate::memo::MAX_SMALL_CAVES;
+
+mod dot;
+
 // Doing a proper graph in Rust is HARD. So we cheat.
 type CaveData = String;
 
+/// Caves by name, and which other caves they lead to.
+///
+/// Handy for building, but walking it means comparing strings, so hand it to
+/// `CaveSystem` before doing that.
 #[derive(Debug)]
-struct LiteGraph {
+pub struct LiteGraph {
     adjacencies: FxHashMap<CaveData, Vec<CaveData>>,
 }
 
 impl LiteGraph {
-    fn new() -> Self {
+    pub fn new() -> Self {
         return Self {
             adjacencies: FxHashMap::default(),
         };
     }
 
+    /// Build a graph from `a-b` lines, one tunnel per line.
+    pub fn from_lines(input: &[String]) -> Self {
+        let mut graph = LiteGraph::new();
+        for line in input.iter() {
+            let (src, dst) = line.split_once("-").unwrap();
+            graph.add_undirected_edge(&src.to_string(), &dst.to_string());
+        }
+        return graph;
+    }
+
     fn add_directed_edge(&mut self, src: &CaveData, dst: &CaveData) {
         self.adjacencies
             .entry(src.to_string())
@@ -24,78 +46,45 @@ impl LiteGraph {
             .push(dst.to_string());
     }
 
-    fn add_undirected_edge(&mut self, a: &CaveData, b: &CaveData) {
+    pub fn add_undirected_edge(&mut self, a: &CaveData, b: &CaveData) {
         self.add_directed_edge(a, b);
         self.add_directed_edge(b, a);
     }
 
-    fn adj(&self, node: &CaveData) -> impl Iterator<Item = &CaveData> {
+    pub fn adj(&self, node: &CaveData) -> impl Iterator<Item = &CaveData> {
         return self.adjacencies[node].iter();
     }
 
-    fn is_end(&self, node: &CaveData) -> bool {
+    /// Every cave in the graph, in no particular order.
+    pub fn caves(&self) -> impl Iterator<Item = &CaveData> {
+        return self.adjacencies.keys();
+    }
+
+    pub fn is_end(&self, node: &CaveData) -> bool {
         return node == "end";
     }
 
-    fn is_start(&self, node: &CaveData) -> bool {
+    pub fn is_start(&self, node: &CaveData) -> bool {
         return node == "start";
     }
 
-    fn is_large(&self, node: &CaveData) -> bool {
-        return node.to_uppercase() == *node;
+    pub fn is_large(&self, node: &CaveData) -> bool {
+        return !node.chars().any(|c| c.is_lowercase());
     }
 }
 
-fn recurse_walk_counting_paths<'a>(
-    graph: &LiteGraph,
-    node: &'a CaveData,
-    mut seen: FxHashSet<&'a CaveData>,
-) -> usize {
-    if seen.contains(&node) {
-        return 0;
-    }
-    if graph.is_end(node) {
-        return 1;
+impl Default for LiteGraph {
+    fn default() -> Self {
+        return Self::new();
     }
-    if !graph.is_large(node) {
-        seen.insert(node);
-    }
-    return graph
-        .adj(node)
-        .map(|node| recurse_walk_counting_paths(graph, node, seen.clone()))
-        .sum();
 }
 
-// Not happy with this solution due to time it takes
-// Solution to 2: 96528
-//
-// real    0m1.427s
-// user    0m1.427s
-// sys     0m0.000s
-fn recurse_walk_counting_paths_allow_a_double<'a>(
-    graph: &LiteGraph,
-    node: &'a CaveData,
-    mut seen: FxHashSet<&'a CaveData>,
-    mut double: bool,
-) -> usize {
-    if seen.contains(&node) {
-        if double {
-            return 0;
-        } else {
-            double = true;
-        }
-    }
-    if graph.is_end(node) {
-        return 1;
-    }
-    if !graph.is_large(node) {
-        seen.insert(node);
-    }
-    return graph
-        .adj(node)
-        .filter(|&node| !graph.is_start(node))
-        .map(|node| recurse_walk_counting_paths_allow_a_double(graph, node, seen.clone(), double))
-        .sum();
+// Count paths from "start" to "end" of the puzzle input.
+fn count_puzzle_paths(input: &Vec<String>, policy: &VisitPolicy) -> usize {
+    let caves = CaveSystem::new(&LiteGraph::from_lines(input));
+    let start = caves.id("start").expect("No start cave");
+    let end = caves.id("end").expect("No end cave");
+    return caves.count_paths_memo(start, end, policy).unwrap() as usize;
 }
 
 /// Walk through all the paths visting small caves only once.
@@ -124,12 +113,7 @@ fn recurse_walk_counting_paths_allow_a_double<'a>(
 /// assert_eq!(day12::puzzle_a(&input), 226);
 /// ```
 pub fn puzzle_a(input: &Vec<String>) -> usize {
-    let mut graph = LiteGraph::new();
-    for line in input.iter() {
-        let (src, dst) = line.split_once("-").unwrap();
-        graph.add_undirected_edge(&src.to_string(), &dst.to_string());
-    }
-    return recurse_walk_counting_paths(&graph, &"start".to_string(), FxHashSet::default());
+    return count_puzzle_paths(input, &VisitPolicy::once());
 }
 
 /// Walk through all the paths visting small caves only once, except for one double
@@ -158,17 +142,7 @@ pub fn puzzle_a(input: &Vec<String>) -> usize {
 /// assert_eq!(day12::puzzle_b(&input), 3509);
 /// ```
 pub fn puzzle_b(input: &Vec<String>) -> usize {
-    let mut graph = LiteGraph::new();
-    for line in input.iter() {
-        let (src, dst) = line.split_once("-").unwrap();
-        graph.add_undirected_edge(&src.to_string(), &dst.to_string());
-    }
-    return recurse_walk_counting_paths_allow_a_double(
-        &graph,
-        &"start".to_string(),
-        FxHashSet::default(),
-        false,
-    );
+    return count_puzzle_paths(input, &VisitPolicy::one_double());
 }
 
 #[cfg(test)]
@@ -221,20 +195,190 @@ mod tests {
     }
 
     #[test]
-    fn test_recurse_walk_counting_paths() {
-        let (graph, start, _) = make_cave_systems();
+    fn test_walk_counting_paths() {
+        let (graph, start, end) = make_cave_systems();
+        let caves = CaveSystem::new(&graph);
+        let (start, end) = (caves.id(&start).unwrap(), caves.id(&end).unwrap());
+        assert_eq!(caves.count_paths(start, end, &VisitPolicy::once()), Ok(10));
         assert_eq!(
-            recurse_walk_counting_paths(&graph, &start, FxHashSet::default()),
+            caves
+                .paths(start, end, &VisitPolicy::once())
+                .unwrap()
+                .count(),
             10
         );
     }
 
     #[test]
-    fn test_recurse_walk_counting_path_a_doubles() {
-        let (graph, start, _) = make_cave_systems();
+    fn test_walk_counting_path_a_doubles() {
+        let (graph, start, end) = make_cave_systems();
+        let caves = CaveSystem::new(&graph);
+        let (start, end) = (caves.id(&start).unwrap(), caves.id(&end).unwrap());
+        assert_eq!(
+            caves.count_paths(start, end, &VisitPolicy::one_double()),
+            Ok(36)
+        );
         assert_eq!(
-            recurse_walk_counting_paths_allow_a_double(&graph, &start, FxHashSet::default(), false),
+            caves
+                .paths(start, end, &VisitPolicy::one_double())
+                .unwrap()
+                .count(),
             36
         );
     }
+
+    #[test]
+    fn test_paths_respect_policy() {
+        let (graph, start, end) = make_cave_systems();
+        let caves = CaveSystem::new(&graph);
+        let (start, end) = (caves.id(&start).unwrap(), caves.id(&end).unwrap());
+        let mut policy = VisitPolicy::one_double();
+        policy.max_visits = 2;
+        policy.forbidden.insert(caves.id("d").unwrap());
+        let paths: Vec<Vec<CaveId>> = caves.paths(start, end, &policy).unwrap().collect();
+        assert_eq!(Ok(paths.len()), caves.count_paths(start, end, &policy));
+        for path in paths.iter() {
+            assert_eq!(path.first(), Some(&start));
+            assert_eq!(path.last(), Some(&end));
+            let mut triples = 0;
+            for cave in 0..caves.len() {
+                let visits = path.iter().filter(|c| **c == cave).count();
+                if caves.is_large(cave) {
+                    continue;
+                }
+                assert!(visits <= 3);
+                if visits == 3 {
+                    triples += 1;
+                }
+            }
+            assert!(triples <= 1);
+            assert!(!path.contains(&caves.id("d").unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_large_start_and_end_next_to_large_caves() {
+        let lines: Vec<String> = ["A-B", "B-c", "c-D", "D-E", "A-c"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        let caves = CaveSystem::new(&LiteGraph::from_lines(&lines));
+        let id = |name: &str| caves.id(name).unwrap();
+        // A,c,D,E and A,B,c,D,E, with A the start and E the end.
+        assert_eq!(
+            caves.count_paths(id("A"), id("E"), &VisitPolicy::once()),
+            Ok(2)
+        );
+        assert_eq!(
+            caves.count_paths_memo(id("A"), id("E"), &VisitPolicy::once()),
+            Ok(2)
+        );
+        assert_eq!(
+            caves
+                .paths(id("A"), id("E"), &VisitPolicy::once())
+                .unwrap()
+                .count(),
+            2
+        );
+        // B can bounce off A when A isn't the start.
+        assert!(caves
+            .count_paths(id("c"), id("E"), &VisitPolicy::once())
+            .is_err());
+    }
+
+    // Small made up cave systems, with no two large caves next to each other.
+    fn generate_cave_lines(seed: u64, small: usize, large: usize, edges: usize) -> Vec<String> {
+        let mut rng = filelib::TestRng::new(seed);
+        let mut names: Vec<String> = vec!["start".to_string(), "end".to_string()];
+        names.extend((0..small).map(|i| format!("s{}", i)));
+        names.extend((0..large).map(|i| format!("L{}", i)));
+        let mut lines = Vec::new();
+        while lines.len() < edges {
+            let a = &names[rng.below(names.len() as u64) as usize];
+            let b = &names[rng.below(names.len() as u64) as usize];
+            let both_large = a.starts_with('L') && b.starts_with('L');
+            if a != b && !both_large {
+                lines.push(format!("{}-{}", a, b));
+            }
+        }
+        return lines;
+    }
+
+    #[test]
+    fn test_memo_matches_walker() {
+        let mut forbidding_mattered = 0;
+        for seed in 0..40 {
+            let lines = generate_cave_lines(seed, 6, 2, 14);
+            let caves = CaveSystem::new(&LiteGraph::from_lines(&lines));
+            let (start, end) = match (caves.id("start"), caves.id("end")) {
+                (Some(s), Some(e)) => (s, e),
+                _ => continue,
+            };
+            let small: Vec<CaveId> = (0..caves.len())
+                .filter(|c| !caves.is_large(*c) && *c != start && *c != end)
+                .collect();
+            let mut forbid_one = VisitPolicy::one_double();
+            forbid_one
+                .forbidden
+                .insert(small[seed as usize % small.len()]);
+            let open = caves.count_paths(start, end, &VisitPolicy::one_double());
+            let forbidden = caves.count_paths(start, end, &forbid_one);
+            if forbidden != open {
+                forbidding_mattered += 1;
+            }
+            for policy in [VisitPolicy::once(), VisitPolicy::one_double(), forbid_one] {
+                assert_eq!(
+                    caves.count_paths_memo(start, end, &policy),
+                    caves.count_paths(start, end, &policy).map(|n| n as u128),
+                    "{:?} {:?}",
+                    lines,
+                    policy
+                );
+            }
+        }
+        assert!(forbidding_mattered > 0);
+    }
+
+    // A chain of small caves, each link with a large cave beside it to go around by.
+    fn chain_with_bypasses(length: usize) -> Vec<String> {
+        let mut lines = vec!["start-s0".to_string(), format!("end-s{}", length - 1)];
+        for i in 1..length {
+            lines.push(format!("s{}-s{}", i - 1, i));
+            lines.push(format!("s{}-L{}", i - 1, i));
+            lines.push(format!("L{}-s{}", i, i));
+        }
+        return lines;
+    }
+
+    #[test]
+    fn test_memo_many_small_caves() {
+        for length in 2..8 {
+            let caves = CaveSystem::new(&LiteGraph::from_lines(&chain_with_bypasses(length)));
+            let (start, end) = (caves.id("start").unwrap(), caves.id("end").unwrap());
+            for policy in [VisitPolicy::once(), VisitPolicy::one_double()] {
+                assert_eq!(
+                    caves.count_paths_memo(start, end, &policy),
+                    caves.count_paths(start, end, &policy).map(|n| n as u128)
+                );
+            }
+        }
+
+        // Far too many paths to walk, but only a few hundred states.
+        let caves = CaveSystem::new(&LiteGraph::from_lines(&chain_with_bypasses(64)));
+        let (start, end) = (caves.id("start").unwrap(), caves.id("end").unwrap());
+        assert_eq!(
+            caves.count_paths_memo(start, end, &VisitPolicy::once()),
+            Ok(1 << 63)
+        );
+        assert!(caves
+            .count_paths_memo(start, end, &VisitPolicy::one_double())
+            .is_ok());
+
+        // One small cave too many.
+        let caves = CaveSystem::new(&LiteGraph::from_lines(&chain_with_bypasses(65)));
+        let (start, end) = (caves.id("start").unwrap(), caves.id("end").unwrap());
+        assert!(caves
+            .count_paths_memo(start, end, &VisitPolicy::once())
+            .is_err());
+    }
 }
@@ -1,11 +1,23 @@
 use day12::load_no_blanks;
 use day12::puzzle_a;
 use day12::puzzle_b;
+use day12::LiteGraph;
+use std::env;
+use std::fs;
 
 fn main() {
     let filename = "input";
     let all_lines = load_no_blanks(filename);
 
+    // --dot file [start,A,b,end] writes the caves out for graphviz instead of solving.
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--dot") {
+        let graph = LiteGraph::from_lines(&all_lines);
+        let path: Option<Vec<&str>> = args.get(i + 2).map(|p| p.split(',').collect());
+        fs::write(&args[i + 1], graph.to_dot(path.as_deref())).unwrap();
+        return;
+    }
+
     let value_a = puzzle_a(&all_lines);
     println!("Solution to 1: {}", value_a);
 
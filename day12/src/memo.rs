@@ -0,0 +1,125 @@
+use rustc_hash::FxHashMap;
+
+use crate::{CaveId, CaveSystem, VisitPolicy};
+
+/// Most small caves `CaveSystem::count_paths_memo` can keep track of.
+pub const MAX_SMALL_CAVES: usize = 64;
+
+// Where the search is, which small caves it has already been through, and whether the
+// one double visit has been used up. Nothing else about the path so far matters for how
+// many ways there are to finish it.
+type State = (CaveId, u64, bool);
+
+struct Counter<'a> {
+    caves: &'a CaveSystem,
+    policy: &'a VisitPolicy,
+    start: CaveId,
+    end: CaveId,
+    // Bit for each small cave, None for large ones.
+    bits: Vec<Option<u32>>,
+    cache: FxHashMap<State, u128>,
+}
+
+impl<'a> Counter<'a> {
+    fn count(&mut self, cave: CaveId, seen: u64, double_used: bool) -> Option<u128> {
+        if cave == self.end {
+            return Some(1);
+        }
+        if let Some(total) = self.cache.get(&(cave, seen, double_used)) {
+            return Some(*total);
+        }
+
+        let mut total: u128 = 0;
+        for next in self.caves.neighbours(cave).iter() {
+            let next = *next;
+            if next == self.start || self.policy.forbidden.contains(&next) {
+                continue;
+            }
+            let sub = match self.bits[next] {
+                None => self.count(next, seen, double_used)?,
+                Some(bit) if seen & (1 << bit) == 0 => {
+                    self.count(next, seen | (1 << bit), double_used)?
+                }
+                Some(_) if !double_used && self.policy.allowed_doubles > 0 => {
+                    self.count(next, seen, true)?
+                }
+                Some(_) => continue,
+            };
+            total = total.checked_add(sub)?;
+        }
+
+        self.cache.insert((cave, seen, double_used), total);
+        return Some(total);
+    }
+}
+
+impl CaveSystem {
+    /// Count paths from start to end by memoising on which small caves have been visited,
+    /// rather than walking every path.
+    ///
+    /// How long it takes depends on how many different sets of visited small caves can come
+    /// up on the way, not how many paths there are. That's small for long chains of caves,
+    /// but grid-like systems can still have exponentially many.
+    ///
+    /// Handles the puzzle's policies: small caves once each, and at most one of them twice,
+    /// plus any forbidden caves. It gives an Err for anything else, if there are more than
+    /// `MAX_SMALL_CAVES` small caves, if two large caves are next to each other (so there
+    /// are infinitely many paths), or if the count is too big for a u128.
+    ///
+    /// ```
+    /// use day12::{CaveSystem, LiteGraph, VisitPolicy};
+    /// let input = vec!["start-A".to_string(), "start-b".to_string(), "A-c".to_string(),
+    ///     "A-b".to_string(), "b-d".to_string(), "A-end".to_string(), "b-end".to_string()];
+    /// let caves = CaveSystem::new(&LiteGraph::from_lines(&input));
+    /// let (start, end) = (caves.id("start").unwrap(), caves.id("end").unwrap());
+    /// assert_eq!(caves.count_paths_memo(start, end, &VisitPolicy::once()), Ok(10));
+    /// assert_eq!(caves.count_paths_memo(start, end, &VisitPolicy::one_double()), Ok(36));
+    ///
+    /// let mut policy = VisitPolicy::once();
+    /// policy.max_visits = 2;
+    /// assert!(caves.count_paths_memo(start, end, &policy).is_err());
+    /// ```
+    pub fn count_paths_memo(
+        &self,
+        start: CaveId,
+        end: CaveId,
+        policy: &VisitPolicy,
+    ) -> Result<u128, String> {
+        if policy.max_visits != 1 || policy.allowed_doubles > 1 {
+            return Err(format!(
+                "Can only count visiting small caves once with at most one double, not {:?}",
+                policy
+            ));
+        }
+        self.check_large_caves(start, end)?;
+
+        let mut bits = vec![None; self.len()];
+        let mut next_bit = 0;
+        for (cave, bit) in bits.iter_mut().enumerate() {
+            // Start can't be gone back to and end finishes the path, so neither needs a bit.
+            let tracked = cave != start && cave != end && !policy.forbidden.contains(&cave);
+            if !self.is_large(cave) && tracked {
+                if next_bit as usize == MAX_SMALL_CAVES {
+                    return Err(format!(
+                        "More than {} small caves to keep track of",
+                        MAX_SMALL_CAVES
+                    ));
+                }
+                *bit = Some(next_bit);
+                next_bit += 1;
+            }
+        }
+
+        let mut counter = Counter {
+            caves: self,
+            policy,
+            start,
+            end,
+            bits,
+            cache: FxHashMap::default(),
+        };
+        return counter
+            .count(start, 0, false)
+            .ok_or_else(|| "Too many paths to count in a u128".to_string());
+    }
+}
@@ -170,4 +170,20 @@ mod tests {
         assert_eq!(twice_folded.get_width(), 5);
         assert_eq!(twice_folded.get_height(), 7);
     }
+
+    #[test]
+    fn test_sheet_matches_paper() {
+        let paper = make_paper();
+        let dots: FxHashSet<BoardCoordinate> = paper
+            .board
+            .coord_iter()
+            .filter(|c| paper.board.get_value(*c).unwrap())
+            .collect();
+        let sheet = crate::DotSheet::new(&dots);
+        let folds = vec![crate::Fold::Horizontal(7), crate::Fold::Vertical(5)];
+        let folded_sheet = sheet.fold_all(&folds);
+        let folded_paper = paper.fold_horizontal(7).fold_vertical(5);
+        assert_eq!(folded_sheet.fancy_print(), folded_paper.fancy_print());
+        assert_eq!(folded_sheet.get_num_dots(), folded_paper.get_num_dots());
+    }
 }
@@ -1,6 +1,8 @@
 // First question, do I use sparse maps and shrink them, or use an actual board representation?
 // As I will be doing a lot of recreation, adding, and subtracting, I decice the board is more
 // efficient.
+// Later: folds turned out not to always be down the middle, so the puzzles now use the sparse
+// `DotSheet`, which also never has to allocate a grid for the whole sheet.
 pub use boardlib::BoardCoordinate;
 pub use rustc_hash::FxHashSet;
 
@@ -9,11 +11,16 @@ mod fold;
 pub use crate::fold::Fold;
 
 mod parse;
-use crate::parse::coords_to_bool;
-pub use crate::parse::{parse_coords, parse_folds};
+pub use crate::parse::{coords_to_bool, parse_coords, parse_folds};
 
 mod foldable;
-use crate::foldable::FoldablePaper;
+pub use crate::foldable::FoldablePaper;
+
+mod sheet;
+pub use crate::sheet::{BoundingBox, DotSheet};
+
+mod ocr;
+pub use crate::ocr::{read_letters, read_letters_in_font, Font, UnknownGlyph};
 
 /// Perform the first fold and count the dots
 ///
@@ -43,32 +50,11 @@ use crate::foldable::FoldablePaper;
 /// assert_eq!(day13::puzzle_a(&dot_coords, &folds), 17);
 /// ```
 pub fn puzzle_a(coords: &FxHashSet<BoardCoordinate>, folds: &Vec<Fold>) -> usize {
-    let mut width: usize = 0;
-    let mut height: usize = 0;
-    for v in coords.iter() {
-        if v.x > width {
-            width = v.x;
-        }
-        if v.y > height {
-            height = v.y;
-        }
-    }
-    // Width and height are 1 indexed, not 0 indexed.
-    width += 1;
-    height += 1;
-
-    let values = coords_to_bool(coords, width, height);
-    let paper = FoldablePaper::new(values, width, height);
-    let folded_paper: FoldablePaper;
-    if let Some(fold) = folds.first() {
-        match fold {
-            Fold::Vertical(a) => folded_paper = paper.fold_vertical(*a),
-            Fold::Horizontal(a) => folded_paper = paper.fold_horizontal(*a),
-        }
-    } else {
-        return 0;
-    }
-    return folded_paper.get_num_dots();
+    let paper = DotSheet::new(coords);
+    return match folds.first() {
+        Some(fold) => paper.fold(fold).get_num_dots(),
+        None => 0,
+    };
 }
 
 /// Perform all the folds, then figure out what letters there are
@@ -99,30 +85,36 @@ pub fn puzzle_a(coords: &FxHashSet<BoardCoordinate>, folds: &Vec<Fold>) -> usize
 /// assert_eq!(day13::puzzle_b(&dot_coords, &folds), "█████\n█   █\n█   █\n█   █\n█████\n     \n     ");
 /// ```
 pub fn puzzle_b(coords: &FxHashSet<BoardCoordinate>, folds: &Vec<Fold>) -> String {
-    let mut width: usize = 0;
-    let mut height: usize = 0;
-    for v in coords.iter() {
-        if v.x > width {
-            width = v.x;
-        }
-        if v.y > height {
-            height = v.y;
-        }
-    }
-    // Width and height are 1 indexed, not 0 indexed.
-    width += 1;
-    height += 1;
-
-    let values = coords_to_bool(coords, width, height);
-    let paper = FoldablePaper::new(values, width, height);
-    let mut folded_paper: FoldablePaper = paper;
-    for fold in folds {
-        match fold {
-            Fold::Vertical(a) => folded_paper = folded_paper.fold_vertical(*a),
-            Fold::Horizontal(a) => folded_paper = folded_paper.fold_horizontal(*a),
-        }
-    }
-
-    // I'm too lazy to build an OCR that can flip letters if they are upside down...
+    let folded_paper = DotSheet::new(coords).fold_all(folds);
     return folded_paper.fancy_print();
 }
+
+/// Perform all the folds, then read the letters off the paper
+///
+/// ```
+/// use boardlib::BoardCoordinate;
+/// use rustc_hash::FxHashSet;
+/// let mut dot_coords: FxHashSet<BoardCoordinate> = FxHashSet::default();
+/// for y in 0..6 {
+///     dot_coords.insert(BoardCoordinate::new(0, y));
+///     dot_coords.insert(BoardCoordinate::new(8, y));
+/// }
+/// dot_coords.insert(BoardCoordinate::new(1, 5));
+/// dot_coords.insert(BoardCoordinate::new(2, 5));
+/// dot_coords.insert(BoardCoordinate::new(3, 5));
+/// // Folding at x=4 lays the second stroke over the first, making an L.
+/// let folds = vec![day13::Fold::Vertical(4)];
+/// assert_eq!(day13::puzzle_b_letters(&dot_coords, &folds), Ok("L".to_string()));
+///
+/// // Unfolded, the second stroke on its own isn't a letter.
+/// let err = day13::puzzle_b_letters(&dot_coords, &vec![]).unwrap_err();
+/// assert_eq!(err.index, 1);
+/// assert_eq!(err.position, BoardCoordinate::new(5, 0));
+/// ```
+pub fn puzzle_b_letters(
+    coords: &FxHashSet<BoardCoordinate>,
+    folds: &Vec<Fold>,
+) -> Result<String, UnknownGlyph> {
+    let folded_paper = DotSheet::new(coords).fold_all(folds);
+    return read_letters(&folded_paper.to_board());
+}
@@ -3,6 +3,7 @@ use day13::parse_coords;
 use day13::parse_folds;
 use day13::puzzle_a;
 use day13::puzzle_b;
+use day13::puzzle_b_letters;
 use day13::split_lines_by_blanks;
 use day13::Fold;
 use day13::FxHashSet;
@@ -27,4 +28,8 @@ fn main() {
 
     let value_b = puzzle_b(&coords, &folds);
     println!("Solution to 2: \n{}", value_b);
+    match puzzle_b_letters(&coords, &folds) {
+        Ok(letters) => println!("Which reads: {}", letters),
+        Err(e) => println!("Couldn't read it: {}", e),
+    }
 }
@@ -0,0 +1,300 @@
+use boardlib::{Board, BoardCoordinate, BoardTraversable};
+use std::fmt;
+
+/// The dot matrix fonts Advent of Code draws letters in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    /// 4x6 letters, one column apart. What this puzzle uses.
+    Small,
+    /// 6x10 letters, two columns apart.
+    Large,
+}
+
+// Every known letter, drawn row by row.
+const SMALL_GLYPHS: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+const LARGE_GLYPHS: [(char, &str); 15] = [
+    (
+        'A',
+        "..##...#..#.#....##....##....########....##....##....##....#",
+    ),
+    (
+        'B',
+        "#####.#....##....##....######.#....##....##....##....######.",
+    ),
+    (
+        'C',
+        ".####.#....##.....#.....#.....#.....#.....#.....#....#.####.",
+    ),
+    (
+        'E',
+        "#######.....#.....#.....#####.#.....#.....#.....#.....######",
+    ),
+    (
+        'F',
+        "#######.....#.....#.....#####.#.....#.....#.....#.....#.....",
+    ),
+    (
+        'G',
+        ".####.#....##.....#.....#.....#..####....##....##...##.###.#",
+    ),
+    (
+        'H',
+        "#....##....##....##....########....##....##....##....##....#",
+    ),
+    (
+        'J',
+        "...###....#.....#.....#.....#.....#.....#.#...#.#...#..###..",
+    ),
+    (
+        'K',
+        "#....##...#.#..#..#.#...##....##....#.#...#..#..#...#.#....#",
+    ),
+    (
+        'L',
+        "#.....#.....#.....#.....#.....#.....#.....#.....#.....######",
+    ),
+    (
+        'N',
+        "#....###...###...##.#..##.#..##..#.##..#.##...###...###....#",
+    ),
+    (
+        'P',
+        "#####.#....##....##....######.#.....#.....#.....#.....#.....",
+    ),
+    (
+        'R',
+        "#####.#....##....##....######.#..#..#...#.#...#.#....##....#",
+    ),
+    (
+        'X',
+        "#....##....#.#..#..#..#...##....##...#..#..#..#.#....##....#",
+    ),
+    (
+        'Z',
+        "######.....#.....#....#....#....#....#....#.....#.....######",
+    ),
+];
+
+impl Font {
+    fn width(&self) -> usize {
+        return match self {
+            Font::Small => 4,
+            Font::Large => 6,
+        };
+    }
+
+    fn height(&self) -> usize {
+        return match self {
+            Font::Small => 6,
+            Font::Large => 10,
+        };
+    }
+
+    // Distance from the start of one letter to the next.
+    fn pitch(&self) -> usize {
+        return match self {
+            Font::Small => 5,
+            Font::Large => 8,
+        };
+    }
+
+    fn glyphs(&self) -> &'static [(char, &'static str)] {
+        return match self {
+            Font::Small => &SMALL_GLYPHS,
+            Font::Large => &LARGE_GLYPHS,
+        };
+    }
+}
+
+/// A letter that didn't match anything in the font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownGlyph {
+    /// Which letter it was, counting from 0.
+    pub index: usize,
+    /// Where its top left corner is on the board.
+    pub position: BoardCoordinate,
+    /// What it looked like, `#` and `.` rows split by newlines.
+    pub glyph: String,
+}
+
+impl fmt::Display for UnknownGlyph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(
+            f,
+            "Unknown letter {} at ({}, {}):\n{}",
+            self.index, self.position.x, self.position.y, self.glyph
+        );
+    }
+}
+
+/// Read the letters drawn on a board, using whichever font fits its height.
+///
+/// Boards 10 or more rows tall are read as the large font, anything shorter as the small one.
+///
+/// ```
+/// use boardlib::Board;
+/// let art = [
+///     "#..#..###",
+///     "#..#...#.",
+///     "####...#.",
+///     "#..#...#.",
+///     "#..#...#.",
+///     "#..#..###",
+/// ];
+/// let values: Vec<bool> = art.iter().flat_map(|row| row.chars().map(|c| c == '#')).collect();
+/// let board = Board::new(9, 6, values);
+/// assert_eq!(day13::read_letters(&board), Ok("HI".to_string()));
+/// ```
+pub fn read_letters(board: &Board<bool>) -> Result<String, UnknownGlyph> {
+    let font = if board.get_height() >= Font::Large.height() {
+        Font::Large
+    } else {
+        Font::Small
+    };
+    return read_letters_in_font(board, font);
+}
+
+/// Read the letters drawn on a board in a given font.
+///
+/// Letters are expected every few columns from the left edge, starting at the top row.
+/// Blank letters are read as spaces, and any at the end are dropped.
+pub fn read_letters_in_font(board: &Board<bool>, font: Font) -> Result<String, UnknownGlyph> {
+    let mut text = String::new();
+    let mut index = 0;
+    let mut left = 0;
+    while left < board.get_width() {
+        let mut glyph = String::new();
+        for y in 0..font.height() {
+            for x in left..left + font.width() {
+                let lit = board.get_value(BoardCoordinate::new(x, y)).unwrap_or(false);
+                glyph.push(if lit { '#' } else { '.' });
+            }
+        }
+
+        if !glyph.contains('#') {
+            text.push(' ');
+        } else {
+            match font.glyphs().iter().find(|(_, g)| *g == glyph) {
+                Some((letter, _)) => text.push(*letter),
+                None => {
+                    let rows: Vec<&str> = (0..font.height())
+                        .map(|row| &glyph[row * font.width()..(row + 1) * font.width()])
+                        .collect();
+                    return Err(UnknownGlyph {
+                        index,
+                        position: BoardCoordinate::new(left, 0),
+                        glyph: rows.join("\n"),
+                    });
+                }
+            }
+        }
+        index += 1;
+        left += font.pitch();
+    }
+    return Ok(text.trim_end().to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board_from_art(art: &[&str]) -> Board<bool> {
+        let values: Vec<bool> = art
+            .iter()
+            .flat_map(|row| row.chars().map(|c| c == '#'))
+            .collect();
+        return Board::new(art[0].len(), art.len(), values);
+    }
+
+    #[test]
+    fn test_glyphs_fit_fonts() {
+        for font in [Font::Small, Font::Large] {
+            for (letter, glyph) in font.glyphs() {
+                assert_eq!(
+                    glyph.len(),
+                    font.width() * font.height(),
+                    "{} is the wrong size",
+                    letter
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_small() {
+        let board = board_from_art(&[
+            "###..####.#..#.####..##..###..#..#.#....",
+            "#..#.#....#..#....#.#..#.#..#.#..#.#....",
+            "#..#.###..####...#..#....#..#.#..#.#....",
+            "###..#....#..#..#...#.##.###..#..#.#....",
+            "#.#..#....#..#.#....#..#.#.#..#..#.#....",
+            "#..#.####.#..#.####..###.#..#..##..####.",
+        ]);
+        assert_eq!(read_letters(&board), Ok("REHZGRUL".to_string()));
+    }
+
+    #[test]
+    fn test_read_large() {
+        let board = board_from_art(&[
+            "#....#..######..",
+            "#....#.......#..",
+            ".#..#........#..",
+            ".#..#.......#...",
+            "..##.......#....",
+            "..##......#.....",
+            ".#..#....#......",
+            ".#..#...#.......",
+            "#....#..#.......",
+            "#....#..######..",
+        ]);
+        assert_eq!(read_letters(&board), Ok("XZ".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_glyph() {
+        let board = board_from_art(&[
+            "#..#.#.#.",
+            "#..#.#.#.",
+            "####.#.#.",
+            "#..#.#.#.",
+            "#..#.#.#.",
+            "#..#.#.#.",
+        ]);
+        let err = read_letters(&board).unwrap_err();
+        assert_eq!(err.index, 1);
+        assert_eq!(err.position, BoardCoordinate::new(5, 0));
+        assert_eq!(err.glyph, "#.#.\n#.#.\n#.#.\n#.#.\n#.#.\n#.#.");
+    }
+
+    #[test]
+    fn test_blank_letters() {
+        let board = board_from_art(&[
+            "#.........#...........",
+            "#.........#...........",
+            "#.........#...........",
+            "#.........#...........",
+            "#.........#...........",
+            "####......####........",
+        ]);
+        assert_eq!(read_letters(&board), Ok("L L".to_string()));
+    }
+}
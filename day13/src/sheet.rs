@@ -0,0 +1,217 @@
+use boardlib::{Board, BoardCoordinate};
+use rustc_hash::FxHashSet;
+
+use crate::Fold;
+
+/// Smallest box holding every dot, corners included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min: BoardCoordinate,
+    pub max: BoardCoordinate,
+}
+
+impl BoundingBox {
+    pub fn width(&self) -> usize {
+        return self.max.x - self.min.x + 1;
+    }
+
+    pub fn height(&self) -> usize {
+        return self.max.y - self.min.y + 1;
+    }
+}
+
+/// A sheet of transparent paper that only remembers where its dots are.
+///
+/// Unlike `FoldablePaper` it never builds a full grid, so huge sheets with a handful of
+/// dots are cheap, and folds don't have to be down the middle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotSheet {
+    dots: FxHashSet<BoardCoordinate>,
+    width: usize,
+    height: usize,
+}
+
+// Fold one axis of a sheet `size` long along `line`.
+//
+// Returns the new size and where a position ends up. Whichever side of the crease is
+// longer decides the new size, and the shorter side lines up against the crease, so a
+// fold past the middle overhangs and the top (or left) edge moves.
+fn fold_axis(size: usize, line: usize) -> (usize, impl Fn(usize) -> Option<usize>) {
+    let after = size.saturating_sub(line + 1);
+    // A crease past the far edge still leaves everything up to it.
+    let new_size = line.max(after);
+    let place = move |v: usize| {
+        if v < line {
+            return Some(v + new_size - line);
+        } else if v > line {
+            return Some(new_size + line - v);
+        }
+        // Anything right on the crease is lost in the fold.
+        return None;
+    };
+    return (new_size, place);
+}
+
+impl DotSheet {
+    /// A sheet just big enough for all the dots.
+    pub fn new(dots: &FxHashSet<BoardCoordinate>) -> DotSheet {
+        let width = dots.iter().map(|c| c.x + 1).max().unwrap_or(0);
+        let height = dots.iter().map(|c| c.y + 1).max().unwrap_or(0);
+        return DotSheet::with_size(dots, width, height);
+    }
+
+    /// A sheet of a given size, which may be larger than its dots need.
+    pub fn with_size(dots: &FxHashSet<BoardCoordinate>, width: usize, height: usize) -> DotSheet {
+        assert!(
+            dots.iter().all(|c| c.x < width && c.y < height),
+            "Dots must be on the sheet"
+        );
+        return DotSheet {
+            dots: dots.clone(),
+            width,
+            height,
+        };
+    }
+
+    pub fn get_width(&self) -> usize {
+        return self.width;
+    }
+
+    pub fn get_height(&self) -> usize {
+        return self.height;
+    }
+
+    pub fn get_num_dots(&self) -> usize {
+        return self.dots.len();
+    }
+
+    pub fn dots(&self) -> &FxHashSet<BoardCoordinate> {
+        return &self.dots;
+    }
+
+    /// Fold along any line.
+    ///
+    /// A horizontal fold brings the bottom up over the top, and a vertical one brings the
+    /// right over the left. If the part being folded is longer, it sticks out past the
+    /// old edge, and the sheet is renumbered so its new edge is 0 again.
+    ///
+    /// ```
+    /// use boardlib::BoardCoordinate;
+    /// use rustc_hash::FxHashSet;
+    /// use day13::{DotSheet, Fold};
+    /// let mut dots: FxHashSet<BoardCoordinate> = FxHashSet::default();
+    /// dots.insert(BoardCoordinate::new(0, 0));
+    /// dots.insert(BoardCoordinate::new(0, 9));
+    /// dots.insert(BoardCoordinate::new(1, 2));
+    /// let sheet = DotSheet::new(&dots);
+    ///
+    /// // Rows 0..2 on top, 3..10 below, so the bottom overhangs by 5 rows.
+    /// let folded = sheet.fold(&Fold::Horizontal(2));
+    /// assert_eq!(folded.get_height(), 7);
+    /// assert!(folded.dots().contains(&BoardCoordinate::new(0, 5)));
+    /// assert!(folded.dots().contains(&BoardCoordinate::new(0, 0)));
+    /// // (1, 2) was right on the crease.
+    /// assert_eq!(folded.get_num_dots(), 2);
+    ///
+    /// // Folding short of the middle just shrinks it to the longer side.
+    /// let folded = sheet.fold(&Fold::Horizontal(7));
+    /// assert_eq!(folded.get_height(), 7);
+    /// assert!(folded.dots().contains(&BoardCoordinate::new(0, 5)));
+    ///
+    /// // Folding past the last dot moves nothing, the sheet just reaches the crease.
+    /// let mut dots: FxHashSet<BoardCoordinate> = FxHashSet::default();
+    /// dots.insert(BoardCoordinate::new(0, 0));
+    /// dots.insert(BoardCoordinate::new(0, 1));
+    /// let folded = DotSheet::new(&dots).fold(&Fold::Horizontal(5));
+    /// assert_eq!(folded.get_height(), 5);
+    /// assert_eq!(folded.dots(), &dots);
+    /// ```
+    pub fn fold(&self, fold: &Fold) -> DotSheet {
+        let mut dots: FxHashSet<BoardCoordinate> = FxHashSet::default();
+        let (width, height) = match fold {
+            Fold::Horizontal(line) => {
+                let (height, place) = fold_axis(self.height, *line);
+                for c in self.dots.iter() {
+                    if let Some(y) = place(c.y) {
+                        dots.insert(BoardCoordinate::new(c.x, y));
+                    }
+                }
+                (self.width, height)
+            }
+            Fold::Vertical(line) => {
+                let (width, place) = fold_axis(self.width, *line);
+                for c in self.dots.iter() {
+                    if let Some(x) = place(c.x) {
+                        dots.insert(BoardCoordinate::new(x, c.y));
+                    }
+                }
+                (width, self.height)
+            }
+        };
+        return DotSheet {
+            dots,
+            width,
+            height,
+        };
+    }
+
+    /// Do every fold in turn.
+    pub fn fold_all(&self, folds: &[Fold]) -> DotSheet {
+        let mut sheet = self.clone();
+        for fold in folds {
+            sheet = sheet.fold(fold);
+        }
+        return sheet;
+    }
+
+    /// The box around the dots, which can be smaller than the sheet. None if there are no dots.
+    ///
+    /// ```
+    /// use boardlib::BoardCoordinate;
+    /// use rustc_hash::FxHashSet;
+    /// use day13::{BoundingBox, DotSheet};
+    /// let mut dots: FxHashSet<BoardCoordinate> = FxHashSet::default();
+    /// dots.insert(BoardCoordinate::new(3, 8));
+    /// dots.insert(BoardCoordinate::new(7, 2));
+    /// let bounds = DotSheet::with_size(&dots, 1000, 1000).bounding_box().unwrap();
+    /// assert_eq!(bounds, BoundingBox { min: BoardCoordinate::new(3, 2), max: BoardCoordinate::new(7, 8) });
+    /// assert_eq!((bounds.width(), bounds.height()), (5, 7));
+    /// ```
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let min_x = self.dots.iter().map(|c| c.x).min()?;
+        let min_y = self.dots.iter().map(|c| c.y).min()?;
+        let max_x = self.dots.iter().map(|c| c.x).max()?;
+        let max_y = self.dots.iter().map(|c| c.y).max()?;
+        return Some(BoundingBox {
+            min: BoardCoordinate::new(min_x, min_y),
+            max: BoardCoordinate::new(max_x, max_y),
+        });
+    }
+
+    /// The whole sheet as a grid, for when it has been folded down small enough.
+    pub fn to_board(&self) -> Board<bool> {
+        let mut values = vec![false; self.width * self.height];
+        for c in self.dots.iter() {
+            values[c.x + c.y * self.width] = true;
+        }
+        return Board::new(self.width, self.height, values);
+    }
+
+    /// Draw the sheet the same way `FoldablePaper` does.
+    pub fn fancy_print(&self) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        for y in 0..self.height {
+            let line: String = (0..self.width)
+                .map(|x| {
+                    if self.dots.contains(&BoardCoordinate::new(x, y)) {
+                        '█'
+                    } else {
+                        ' '
+                    }
+                })
+                .collect();
+            lines.push(line);
+        }
+        return lines.join("\n");
+    }
+}
@@ -0,0 +1,320 @@
+use crate::PolyPair;
+use rustc_hash::FxHashMap;
+
+/// Runs pair insertion on counts of pairs rather than the polymer itself.
+///
+/// Every element gets a small id, and pair counts live in a dense `n * n` table indexed by
+/// `left * n + right`, so a step is just a walk over that table. Pairs without a rule are
+/// left as they are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolymerEngine {
+    elements: Vec<char>,
+    template: Vec<usize>,
+    // What gets inserted into each pair, by pair index.
+    insertions: Vec<Option<usize>>,
+}
+
+type Matrix = Vec<Vec<u64>>;
+
+/// Longest polymer `PolymerEngine::polymer` will build.
+pub const MAX_EXACT_LENGTH: usize = 1 << 24;
+
+impl PolymerEngine {
+    /// Intern every element in the template and rules, and index the rules by pair.
+    ///
+    /// Gives an Err if a rule's pair isn't two single elements, or it doesn't insert
+    /// exactly one element.
+    ///
+    /// ```
+    /// use day14::{create_rules, PolymerEngine};
+    /// let rules = create_rules(vec!["CH -> B".to_string(), "HH -> N".to_string()]);
+    /// let engine = PolymerEngine::new("NNCB", &rules).unwrap();
+    /// assert_eq!(engine.elements(), &vec!['B', 'C', 'H', 'N']);
+    /// assert_eq!(engine.id('H'), Some(2));
+    /// assert_eq!(engine.id('X'), None);
+    ///
+    /// let empty = create_rules(vec!["CH -> ".to_string()]);
+    /// assert!(PolymerEngine::new("NNCB", &empty).is_err());
+    /// ```
+    pub fn new(
+        polymer_template: &str,
+        rules: &FxHashMap<PolyPair, String>,
+    ) -> Result<PolymerEngine, String> {
+        let mut elements: Vec<char> = polymer_template.chars().collect();
+        for (pair, insert) in rules.iter() {
+            let single = |s: &str| s.chars().count() == 1;
+            if !single(&pair.a) || !single(&pair.b) || !single(insert) {
+                return Err(format!(
+                    "Rule {}{} -> {} isn't a pair of elements inserting one element",
+                    pair.a, pair.b, insert
+                ));
+            }
+            elements.extend(pair.a.chars());
+            elements.extend(pair.b.chars());
+            elements.extend(insert.chars());
+        }
+        elements.sort();
+        elements.dedup();
+
+        let id = |c: char| elements.binary_search(&c).unwrap();
+        let n = elements.len();
+        let mut insertions = vec![None; n * n];
+        for (pair, insert) in rules.iter() {
+            let a = id(pair.a.chars().next().unwrap());
+            let b = id(pair.b.chars().next().unwrap());
+            insertions[a * n + b] = Some(id(insert.chars().next().unwrap()));
+        }
+        let template = polymer_template.chars().map(id).collect();
+
+        return Ok(PolymerEngine {
+            elements,
+            template,
+            insertions,
+        });
+    }
+
+    /// Every element, in id order.
+    pub fn elements(&self) -> &Vec<char> {
+        return &self.elements;
+    }
+
+    /// The id given to an element.
+    pub fn id(&self, element: char) -> Option<usize> {
+        return self.elements.binary_search(&element).ok();
+    }
+
+    fn num_elements(&self) -> usize {
+        return self.elements.len();
+    }
+
+    fn template_pair_counts(&self) -> Vec<u128> {
+        let n = self.num_elements();
+        let mut counts = vec![0; n * n];
+        for w in self.template.windows(2) {
+            counts[w[0] * n + w[1]] += 1;
+        }
+        return counts;
+    }
+
+    /// How many of each pair there are after the given number of steps, None if a count
+    /// no longer fits in a u128.
+    ///
+    /// The result is indexed by `left_id * number of elements + right_id`.
+    pub fn pair_counts(&self, steps: usize) -> Option<Vec<u128>> {
+        let n = self.num_elements();
+        let mut counts = self.template_pair_counts();
+        for _ in 0..steps {
+            let mut next = vec![0u128; n * n];
+            for (pair, count) in counts.iter().enumerate() {
+                if *count == 0 {
+                    continue;
+                }
+                let (a, b) = (pair / n, pair % n);
+                match self.insertions[pair] {
+                    Some(c) => {
+                        next[a * n + c] = next[a * n + c].checked_add(*count)?;
+                        next[c * n + b] = next[c * n + b].checked_add(*count)?;
+                    }
+                    None => next[pair] = next[pair].checked_add(*count)?,
+                }
+            }
+            counts = next;
+        }
+        return Some(counts);
+    }
+
+    /// How many of each element there are after the given number of steps.
+    ///
+    /// Polymers roughly double each step, so this stops working (returns None) a little
+    /// over 120 steps in.
+    ///
+    /// ```
+    /// use day14::{create_rules, PolymerEngine};
+    /// let rules = create_rules(vec!["NN -> C".to_string(), "NC -> B".to_string(), "CB -> H".to_string()]);
+    /// let engine = PolymerEngine::new("NNCB", &rules).unwrap();
+    /// // NNCB -> NCNBCHB
+    /// assert_eq!(
+    ///     engine.element_counts(1),
+    ///     Some(vec![('B', 2), ('C', 2), ('H', 1), ('N', 2)])
+    /// );
+    /// assert_eq!(engine.element_counts(0), Some(vec![('B', 1), ('C', 1), ('H', 0), ('N', 2)]));
+    /// ```
+    pub fn element_counts(&self, steps: usize) -> Option<Vec<(char, u128)>> {
+        let n = self.num_elements();
+        let pairs = self.pair_counts(steps)?;
+        let mut counts = vec![0u128; n];
+        // Every element is the right half of exactly one pair, except the first,
+        // which never changes.
+        for (pair, count) in pairs.iter().enumerate() {
+            counts[pair % n] = counts[pair % n].checked_add(*count)?;
+        }
+        if let Some(first) = self.template.first() {
+            counts[*first] += 1;
+        }
+        return Some(self.elements.iter().cloned().zip(counts).collect());
+    }
+
+    /// Build the actual polymer after the given number of steps.
+    ///
+    /// The length nearly doubles every step, so this gives up with an Err once it would
+    /// pass `MAX_EXACT_LENGTH`.
+    ///
+    /// ```
+    /// use day14::{create_rules, PolymerEngine};
+    /// let rules = create_rules(vec!["NN -> C".to_string(), "NC -> B".to_string(), "CB -> H".to_string()]);
+    /// let engine = PolymerEngine::new("NNCB", &rules).unwrap();
+    /// assert_eq!(engine.polymer(1), Ok("NCNBCHB".to_string()));
+    /// // Only NC still has a rule to grow from.
+    /// assert_eq!(engine.polymer(2), Ok("NBCNBCHB".to_string()));
+    ///
+    /// // NN -> N doubles the polymer every step.
+    /// let doubling = PolymerEngine::new("NN", &create_rules(vec!["NN -> N".to_string()])).unwrap();
+    /// assert_eq!(doubling.polymer(3), Ok("NNNNNNNNN".to_string()));
+    /// assert!(doubling.polymer(100).is_err());
+    /// ```
+    pub fn polymer(&self, steps: usize) -> Result<String, String> {
+        let mut polymer = self.template.clone();
+        for step in 0..steps {
+            let mut next = Vec::with_capacity(polymer.len() * 2);
+            for w in polymer.windows(2) {
+                next.push(w[0]);
+                if let Some(c) = self.insertions[w[0] * self.num_elements() + w[1]] {
+                    next.push(c);
+                }
+            }
+            next.extend(polymer.last());
+            if next.len() > MAX_EXACT_LENGTH {
+                return Err(format!(
+                    "Polymer is over {} elements long by step {}",
+                    MAX_EXACT_LENGTH,
+                    step + 1
+                ));
+            }
+            polymer = next;
+        }
+        return Ok(polymer.iter().map(|id| self.elements[*id]).collect());
+    }
+
+    /// Build the polymer for real, and make sure counting pairs gives the same element counts.
+    ///
+    /// Gives back the counts if they match, and what differs if they don't.
+    ///
+    /// ```
+    /// use day14::{create_rules, PolymerEngine};
+    /// let rules = create_rules(vec!["NN -> C".to_string(), "NC -> B".to_string(), "CB -> H".to_string()]);
+    /// let engine = PolymerEngine::new("NNCB", &rules).unwrap();
+    /// for steps in 0..10 {
+    ///     assert!(engine.check_exact(steps).is_ok());
+    /// }
+    /// assert_eq!(engine.check_exact(1), Ok(vec![('B', 2), ('C', 2), ('H', 1), ('N', 2)]));
+    /// ```
+    pub fn check_exact(&self, steps: usize) -> Result<Vec<(char, u128)>, String> {
+        let polymer = self.polymer(steps)?;
+        let mut exact: Vec<(char, u128)> = self.elements.iter().map(|c| (*c, 0)).collect();
+        for c in polymer.chars() {
+            exact[self.id(c).unwrap()].1 += 1;
+        }
+        let counted = self
+            .element_counts(steps)
+            .ok_or_else(|| "Counts overflowed".to_string())?;
+        if exact != counted {
+            return Err(format!(
+                "After {} steps the polymer has {:?} but pair counting gives {:?}",
+                steps, exact, counted
+            ));
+        }
+        return Ok(counted);
+    }
+
+    /// Element counts modulo `modulus` after any number of steps.
+    ///
+    /// This raises the pair transition matrix to the power of `steps` by repeated
+    /// squaring, so millions (or trillions) of steps only take a few dozen matrix
+    /// multiplications. Gives an Err for a modulus of 0.
+    ///
+    /// ```
+    /// use day14::{create_rules, PolymerEngine};
+    /// let rules = create_rules(vec!["NN -> C".to_string(), "NC -> B".to_string(), "CB -> H".to_string()]);
+    /// let engine = PolymerEngine::new("NNCB", &rules).unwrap();
+    /// let exact: Vec<u64> = engine.element_counts(30).unwrap().iter().map(|(_, c)| (c % 1_000_000_007) as u64).collect();
+    /// let fast: Vec<u64> = engine.element_counts_mod(30, 1_000_000_007).unwrap().iter().map(|(_, c)| *c).collect();
+    /// assert_eq!(exact, fast);
+    /// assert_eq!(engine.element_counts_mod(5_000_000, 1_000_000_007).unwrap().len(), 4);
+    /// assert!(engine.element_counts_mod(30, 0).is_err());
+    /// ```
+    pub fn element_counts_mod(&self, steps: u64, modulus: u64) -> Result<Vec<(char, u64)>, String> {
+        if modulus == 0 {
+            return Err("Can't count modulo 0".to_string());
+        }
+        let n = self.num_elements();
+        let size = n * n;
+        let m = modulus as u128;
+
+        // transition[to][from] is how many `to` pairs one `from` pair turns into.
+        let mut transition: Matrix = vec![vec![0; size]; size];
+        for (pair, insertion) in self.insertions.iter().enumerate() {
+            let (a, b) = (pair / n, pair % n);
+            match *insertion {
+                Some(c) => {
+                    transition[a * n + c][pair] += 1;
+                    transition[c * n + b][pair] += 1;
+                }
+                None => transition[pair][pair] += 1,
+            }
+        }
+        for row in transition.iter_mut() {
+            for v in row.iter_mut() {
+                *v %= modulus;
+            }
+        }
+
+        let power = matrix_pow(transition, steps, modulus);
+        let start = self.template_pair_counts();
+        let mut counts = vec![0u64; n];
+        for (to, row) in power.iter().enumerate() {
+            let mut total: u128 = 0;
+            for (from, count) in start.iter().enumerate() {
+                total = (total + row[from] as u128 * (*count % m)) % m;
+            }
+            counts[to % n] = ((counts[to % n] as u128 + total) % m) as u64;
+        }
+        if let Some(first) = self.template.first() {
+            counts[*first] = ((counts[*first] as u128 + 1) % m) as u64;
+        }
+        return Ok(self.elements.iter().cloned().zip(counts).collect());
+    }
+}
+
+fn matrix_mul(left: &Matrix, right: &Matrix, modulus: u64) -> Matrix {
+    let size = left.len();
+    let m = modulus as u128;
+    let mut result: Matrix = vec![vec![0; size]; size];
+    for i in 0..size {
+        for k in 0..size {
+            let l = left[i][k] as u128;
+            if l == 0 {
+                continue;
+            }
+            for j in 0..size {
+                result[i][j] = ((result[i][j] as u128 + l * right[k][j] as u128) % m) as u64;
+            }
+        }
+    }
+    return result;
+}
+
+fn matrix_pow(mut base: Matrix, mut exponent: u64, modulus: u64) -> Matrix {
+    let size = base.len();
+    let mut result: Matrix = vec![vec![0; size]; size];
+    for (i, row) in result.iter_mut().enumerate() {
+        row[i] = 1 % modulus;
+    }
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = matrix_mul(&result, &base, modulus);
+        }
+        base = matrix_mul(&base, &base, modulus);
+        exponent >>= 1;
+    }
+    return result;
+}
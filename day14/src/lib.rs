@@ -1,6 +1,11 @@
 pub use filelib::{load, split_lines_by_blanks};
 pub use rustc_hash::{FxHashMap, FxHashSet};
 
+mod engine;
+mod rules;
+pub use crate::engine::{PolymerEngine, MAX_EXACT_LENGTH};
+pub use crate::rules::{validate_rules, RuleProblem};
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct PolyPair {
     pub a: String,
@@ -80,43 +85,13 @@ fn common_puzzle(
     rules: &FxHashMap<PolyPair, String>,
     num_runs: usize,
 ) -> usize {
-    let mut counts = FxHashMap::default();
-    for i in polymer_template.chars().collect::<Vec<_>>().windows(2) {
-        // Create pairs for everything in the template at 1.
-        let pair = PolyPair::from(&i[0].to_string(), &i[1].to_string());
-        *counts.entry(pair).or_default() += 1;
-    }
-
-    for _ in 0..num_runs {
-        let mut current = FxHashMap::default();
-        for (key, c) in counts.iter() {
-            // Have a matching rule, so increase counts of results of inserting.
-            if let Some(letter) = rules.get(key) {
-                let new_pair_left = PolyPair::from(&key.a, &letter);
-                let new_pair_right = PolyPair::from(&letter, &key.b);
-                *current.entry(new_pair_left).or_default() += c;
-                *current.entry(new_pair_right).or_default() += c;
-            }
-        }
-        counts = current;
-    }
-
-    // We have all the counts, extract the max and min. Turns out, fold can help us do this.
-    // We basically count all the occurances of the second characters we have.
-    let mut final_counts = counts.iter().fold(
-        FxHashMap::<String, usize>::default(),
-        |mut m: FxHashMap<String, usize>, (k, v): (&PolyPair, &usize)| {
-            *m.entry(k.b.clone()).or_default() += v;
-            return m;
-        },
-    );
-    // Only thing that will be missing is first character.
-    let first_char: String = polymer_template.chars().nth(0).unwrap().to_string();
-    *final_counts.entry(first_char).or_default() += 1;
-    // Magicy methods to get max and min easy :O
-    let max = final_counts.iter().max_by_key(|(_, i)| *i).unwrap().1;
-    let min = final_counts.iter().min_by_key(|(_, i)| *i).unwrap().1;
-    return max - min;
+    let engine = PolymerEngine::new(polymer_template, rules).unwrap();
+    let counts = engine.element_counts(num_runs).unwrap();
+    // Elements that only show up in rules that never fire shouldn't count as the least common.
+    let present = counts.iter().map(|(_, c)| *c).filter(|c| *c > 0);
+    let max = present.clone().max().unwrap();
+    let min = present.min().unwrap();
+    return (max - min) as usize;
 }
 
 /// Cycle the polymer 10 times, then get the top two character counts, and subtract from each other
@@ -1,6 +1,6 @@
 use day14::puzzle_a;
 use day14::puzzle_b;
-use day14::{create_rules, FxHashMap, PolyPair};
+use day14::{create_rules, validate_rules, FxHashMap, PolyPair};
 use day14::{load, split_lines_by_blanks};
 
 fn main() {
@@ -14,6 +14,9 @@ fn main() {
     template = template_section.first().unwrap().to_string();
 
     let rules_section = sections_split.last().unwrap();
+    for problem in validate_rules(&template, rules_section) {
+        eprintln!("Warning: {}", problem);
+    }
     rules = create_rules(rules_section.to_vec());
 
     let value_a = puzzle_a(&template, &rules);
@@ -0,0 +1,174 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::fmt;
+
+/// Something wrong with a set of insertion rules.
+///
+/// Lines are counted from 0, in the order the rules were given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleProblem {
+    /// The line isn't of the form `AB -> C`.
+    Malformed { line: usize, text: String },
+    /// This pair turns up in the polymer at some point, but nothing is ever inserted into it.
+    Missing { pair: String },
+    /// The same rule was given more than once.
+    Duplicate {
+        pair: String,
+        insert: String,
+        lines: Vec<usize>,
+    },
+    /// The pair has rules inserting different elements.
+    Conflicting {
+        pair: String,
+        inserts: Vec<(usize, String)>,
+    },
+}
+
+impl fmt::Display for RuleProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleProblem::Malformed { line, text } => {
+                write!(f, "line {}: can't read rule '{}'", line, text)
+            }
+            RuleProblem::Missing { pair } => write!(f, "{} appears but has no rule", pair),
+            RuleProblem::Duplicate {
+                pair,
+                insert,
+                lines,
+            } => write!(f, "{} -> {} is repeated on lines {:?}", pair, insert, lines),
+            RuleProblem::Conflicting { pair, inserts } => {
+                let described: Vec<String> = inserts
+                    .iter()
+                    .map(|(line, insert)| format!("{} (line {})", insert, line))
+                    .collect();
+                write!(
+                    f,
+                    "{} has conflicting rules: {}",
+                    pair,
+                    described.join(", ")
+                )
+            }
+        }
+    }
+}
+
+// Split `AB -> C` into its pair and insert, if it looks like a rule.
+fn parse_rule(text: &str) -> Option<(String, String)> {
+    let (pair, insert) = text.split_once("->")?;
+    let (pair, insert) = (pair.trim(), insert.trim());
+    if pair.chars().count() != 2 || insert.chars().count() != 1 {
+        return None;
+    }
+    return Some((pair.to_string(), insert.to_string()));
+}
+
+/// Check rules before they go anywhere near `create_rules`, which just keeps the last
+/// rule it sees for each pair.
+///
+/// A pair only counts as missing if it can actually turn up, starting from the template
+/// and following every rule given.
+///
+/// ```
+/// use day14::RuleProblem;
+/// let rules = vec![
+///     "NN -> C".to_string(),
+///     "NC -> B".to_string(),
+///     "NN -> C".to_string(),
+///     "".to_string(),
+///     "CB -> H".to_string(),
+///     "CB -> N".to_string(),
+///     "oops".to_string(),
+/// ];
+/// let problems = day14::validate_rules("NNCB", &rules);
+/// assert_eq!(problems[0], RuleProblem::Malformed { line: 6, text: "oops".to_string() });
+/// assert!(problems.contains(&RuleProblem::Duplicate {
+///     pair: "NN".to_string(),
+///     insert: "C".to_string(),
+///     lines: vec![0, 2],
+/// }));
+/// assert!(problems.contains(&RuleProblem::Conflicting {
+///     pair: "CB".to_string(),
+///     inserts: vec![(4, "H".to_string()), (5, "N".to_string())],
+/// }));
+/// // NN -> C makes CN, and NC -> B makes BC.
+/// assert!(problems.contains(&RuleProblem::Missing { pair: "CN".to_string() }));
+/// assert!(problems.contains(&RuleProblem::Missing { pair: "BC".to_string() }));
+/// assert!(!problems.contains(&RuleProblem::Missing { pair: "HH".to_string() }));
+/// ```
+pub fn validate_rules(polymer_template: &str, inputs: &[String]) -> Vec<RuleProblem> {
+    let mut problems = Vec::new();
+    // Every insert seen for each pair, with the lines they were on.
+    let mut seen: FxHashMap<String, Vec<(usize, String)>> = FxHashMap::default();
+    let mut order: Vec<String> = Vec::new();
+
+    for (line, text) in inputs.iter().enumerate() {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match parse_rule(text) {
+            Some((pair, insert)) => {
+                if !seen.contains_key(&pair) {
+                    order.push(pair.clone());
+                }
+                seen.entry(pair).or_default().push((line, insert));
+            }
+            None => problems.push(RuleProblem::Malformed {
+                line,
+                text: text.to_string(),
+            }),
+        }
+    }
+
+    for pair in order.iter() {
+        let rules = &seen[pair];
+        let mut inserts: Vec<&String> = rules.iter().map(|(_, i)| i).collect();
+        inserts.sort();
+        inserts.dedup();
+        if inserts.len() > 1 {
+            problems.push(RuleProblem::Conflicting {
+                pair: pair.clone(),
+                inserts: rules.clone(),
+            });
+            continue;
+        }
+        if rules.len() > 1 {
+            problems.push(RuleProblem::Duplicate {
+                pair: pair.clone(),
+                insert: inserts[0].clone(),
+                lines: rules.iter().map(|(line, _)| *line).collect(),
+            });
+        }
+    }
+
+    // Walk out from the template's pairs to every pair the rules can ever make.
+    let template: Vec<char> = polymer_template.chars().collect();
+    let mut reachable: FxHashSet<String> = FxHashSet::default();
+    let mut todo: Vec<String> = template
+        .windows(2)
+        .map(|w| w.iter().collect::<String>())
+        .collect();
+    let mut missing: Vec<String> = Vec::new();
+    while let Some(pair) = todo.pop() {
+        if !reachable.insert(pair.clone()) {
+            continue;
+        }
+        match seen.get(&pair) {
+            Some(rules) => {
+                let (a, b) = pair.split_at(1);
+                for (_, insert) in rules.iter() {
+                    todo.push(format!("{}{}", a, insert));
+                    todo.push(format!("{}{}", insert, b));
+                }
+            }
+            None => missing.push(pair),
+        }
+    }
+    missing.sort();
+    problems.extend(
+        missing
+            .into_iter()
+            .map(|pair| RuleProblem::Missing { pair }),
+    );
+
+    return problems;
+}
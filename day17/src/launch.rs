@@ -0,0 +1,242 @@
+// Working out launches without brute forcing every velocity.
+//
+// After n steps with starting velocity v, ignoring drag, the probe has moved
+// n*v - n(n-1)/2, which is v + (v-1) + ... , a triangular number shifted by n*v.
+// That parabola is symmetric around n = v + 1/2, so the steps where it is at least
+// some value are always one unbroken window, found with the quadratic formula.
+//
+// Horizontally the probe follows the parabola until drag stops it after |v| steps,
+// and then sits still, so it has a single window of steps in the target, which
+// never ends if it stops inside. Vertically it has up to two windows, one on the
+// way up and one on the way down. A velocity pair works if its windows overlap.
+
+/// The steps during which the probe is inside the target along one axis.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct StepWindow {
+    pub first: i64,
+    /// None if the probe never leaves again.
+    pub last: Option<i64>,
+}
+
+impl StepWindow {
+    fn new(first: i64, last: Option<i64>) -> Option<StepWindow> {
+        if let Some(l) = last {
+            if l < first {
+                return None;
+            }
+        }
+        return Some(StepWindow { first, last });
+    }
+
+    /// First step in both windows, if there is one.
+    fn overlap(&self, other: &StepWindow) -> Option<i64> {
+        let first = self.first.max(other.first);
+        let last = match (self.last, other.last) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (Some(a), None) | (None, Some(a)) => Some(a),
+            (None, None) => None,
+        };
+        return StepWindow::new(first, last).map(|w| w.first);
+    }
+}
+
+/// A velocity that hits the target.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Launch {
+    pub dx: i32,
+    pub dy: i32,
+    /// Every position from the launch point up to the first step inside the target.
+    pub trajectory: Vec<(i32, i32)>,
+}
+
+impl Launch {
+    /// Highest y the probe reaches on its way to the target.
+    pub fn max_height(&self) -> i32 {
+        return self.trajectory.iter().map(|(_, y)| *y).max().unwrap();
+    }
+}
+
+// Distance moved after n steps if nothing ever stopped it.
+fn travelled(v: i64, n: i64) -> i64 {
+    return n * v - n * (n - 1) / 2;
+}
+
+// The (possibly non-positive) range of steps where travelled(v, n) >= t, if any.
+fn steps_at_least(v: i64, t: i64) -> Option<(i64, i64)> {
+    // The parabola peaks at v + 1/2, so v and v + 1 share the top integer value.
+    if travelled(v, v) < t {
+        return None;
+    }
+    let disc = ((2 * v + 1) * (2 * v + 1) - 8 * t) as f64;
+    let mut lo = ((2 * v + 1) as f64 - disc.sqrt()).div_euclid(2.0) as i64;
+    // The float estimate can be a little off either way, nudge it onto the exact boundary.
+    while travelled(v, lo) >= t {
+        lo -= 1;
+    }
+    while travelled(v, lo) < t {
+        lo += 1;
+    }
+    return Some((lo, 2 * v + 1 - lo));
+}
+
+// Steps where a probe moving right at v (with drag) is within low..=high.
+fn forward_window(v: i64, low: i64, high: i64) -> Option<StepWindow> {
+    let first = if low <= 0 {
+        1
+    } else {
+        steps_at_least(v, low)?.0.max(1)
+    };
+    let last = if high < 0 {
+        return None;
+    } else {
+        // Once past high it never comes back.
+        steps_at_least(v, high + 1).map(|(leaves, _)| leaves.max(1) - 1)
+    };
+    return StepWindow::new(first, last);
+}
+
+/// Every horizontal velocity that is inside x1..=x2 after some step, and when.
+///
+/// ```
+/// use day17::StepWindow;
+/// let windows = day17::dx_windows(20, 30);
+/// assert_eq!(windows.first(), Some(&(6, StepWindow { first: 5, last: None })));
+/// assert_eq!(windows.last(), Some(&(30, StepWindow { first: 1, last: Some(1) })));
+///
+/// // Targets off to the left mirror the right.
+/// let left = day17::dx_windows(-30, -20);
+/// assert_eq!(left.first(), Some(&(-30, StepWindow { first: 1, last: Some(1) })));
+/// ```
+pub fn dx_windows(x1: i32, x2: i32) -> Vec<(i32, StepWindow)> {
+    let (x1, x2) = (x1 as i64, x2 as i64);
+    let mut result = Vec::new();
+    // The first step lands at dx, so anything beyond the far edge overshoots straight away.
+    for dx in x1.min(0)..=x2.max(0) {
+        let window = match dx.signum() {
+            0 if x1 <= 0 && 0 <= x2 => StepWindow::new(1, None),
+            0 => None,
+            1 => forward_window(dx, x1, x2),
+            _ => forward_window(-dx, -x2, -x1),
+        };
+        if let Some(w) = window {
+            result.push((dx as i32, w));
+        }
+    }
+    return result;
+}
+
+/// The up to two windows of steps where a probe launched upwards at dy is inside y1..=y2.
+///
+/// ```
+/// use day17::StepWindow;
+/// // Up through the target, then back down through it again.
+/// assert_eq!(
+///     day17::dy_windows_for(5, 6, 10),
+///     vec![StepWindow { first: 2, last: Some(2) }, StepWindow { first: 9, last: Some(9) }]
+/// );
+/// assert_eq!(day17::dy_windows_for(9, -10, -5), vec![StepWindow { first: 20, last: Some(20) }]);
+/// ```
+pub fn dy_windows_for(dy: i32, y1: i32, y2: i32) -> Vec<StepWindow> {
+    let (dy, y1, y2) = (dy as i64, y1 as i64, y2 as i64);
+    let (low_first, low_last) = match steps_at_least(dy, y1) {
+        Some(r) => r,
+        None => return Vec::new(),
+    };
+    let first = low_first.max(1);
+    let mut result = Vec::new();
+    match steps_at_least(dy, y2 + 1) {
+        // Never goes above the target, so the whole window counts.
+        None => result.extend(StepWindow::new(first, Some(low_last))),
+        Some((above_first, above_last)) => {
+            result.extend(StepWindow::new(first, Some(low_last.min(above_first - 1))));
+            result.extend(StepWindow::new(first.max(above_last + 1), Some(low_last)));
+        }
+    }
+    return result;
+}
+
+/// Every launch velocity that ends up in the target, wherever the target is.
+///
+/// The target can be in any direction, and can even contain the launch point. The only
+/// thing that can't be answered is when the probe can stop dead over a target that also
+/// spans the launch height, since then every tall enough lob falls back into it, and
+/// there are infinitely many answers.
+///
+/// ```
+/// let launches = day17::solve_launches(20, -10, 30, -5).unwrap();
+/// assert_eq!(launches.len(), 112);
+/// assert_eq!(launches.iter().map(|l| l.max_height()).max(), Some(45));
+/// let l = launches.iter().find(|l| (l.dx, l.dy) == (7, 2)).unwrap();
+/// assert_eq!(l.trajectory, vec![(0, 0), (7, 2), (13, 3), (18, 3), (22, 2), (25, 0), (27, -3), (28, -7)]);
+///
+/// // Up and to the left.
+/// let launches = day17::solve_launches(-30, 5, -20, 10).unwrap();
+/// assert!(launches.iter().all(|l| l.dx < 0 && l.dy > 0));
+/// assert!(launches.iter().any(|l| (l.dx, l.dy) == (-6, 9)));
+///
+/// // The probe can stop over x=0..5, and everything straight up comes back down to y=0.
+/// assert!(day17::solve_launches(0, -5, 5, 5).is_err());
+/// // But nothing stops over x=7..9, so only a few steps can ever land there.
+/// assert_eq!(day17::solve_launches(7, -1, 9, 1).unwrap().len(), 13);
+/// ```
+pub fn solve_launches(x1: i32, y1: i32, x2: i32, y2: i32) -> Result<Vec<Launch>, String> {
+    let x_windows = dx_windows(x1, x2);
+    let straddles_launch = y1 <= 0 && 0 <= y2;
+
+    let mut max_steps: i64 = 0;
+    for (_, w) in x_windows.iter() {
+        match w.last {
+            Some(last) => max_steps = max_steps.max(last),
+            None if straddles_launch => {
+                return Err("Infinitely many launches fall back into this target".to_string());
+            }
+            None => {}
+        }
+    }
+
+    // Anything slower than y1 going down passes it on the first step. Going up, anything
+    // faster than y2 jumps over it and can only come back at height 0 after 2 * dy + 1 steps,
+    // or further down at -(dy + 1) after that.
+    let lowest_dy = y1.min(1);
+    let mut highest_dy = y2.max(-y1 - 1);
+    if straddles_launch {
+        highest_dy = highest_dy.max(((max_steps - 1) / 2) as i32);
+    }
+
+    let mut launches = Vec::new();
+    for (dx, x_window) in x_windows.iter() {
+        for dy in lowest_dy..=highest_dy {
+            let hit = dy_windows_for(dy, y1, y2)
+                .iter()
+                .filter_map(|w| w.overlap(x_window))
+                .min();
+            if let Some(steps) = hit {
+                launches.push(Launch {
+                    dx: *dx,
+                    dy,
+                    trajectory: trajectory(*dx, dy, steps as usize),
+                });
+            }
+        }
+    }
+    return Ok(launches);
+}
+
+/// Every position of the probe, starting at the launch point, for the given number of steps.
+///
+/// ```
+/// assert_eq!(day17::trajectory(-2, 1, 3), vec![(0, 0), (-2, 1), (-3, 1), (-3, 0)]);
+/// ```
+pub fn trajectory(dx: i32, dy: i32, steps: usize) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = (0, 0);
+    let (mut cur_dx, mut cur_dy) = (dx, dy);
+    let mut positions = vec![(x, y)];
+    for _ in 0..steps {
+        x += cur_dx;
+        y += cur_dy;
+        cur_dx -= cur_dx.signum();
+        cur_dy -= 1;
+        positions.push((x, y));
+    }
+    return positions;
+}
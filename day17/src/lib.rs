@@ -2,6 +2,14 @@ pub use filelib::load;
 
 use std::cmp::max;
 
+mod launch;
+pub use crate::launch::{
+    dx_windows, dy_windows_for, solve_launches, trajectory, Launch, StepWindow,
+};
+
+mod plot;
+pub use crate::plot::{plot_positions, render_ascii, render_svg};
+
 /*       +y
  *       |
  *       |
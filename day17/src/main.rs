@@ -2,12 +2,28 @@ use day17::load;
 use day17::load_target_area;
 use day17::puzzle_a;
 use day17::puzzle_b;
+use day17::{plot_positions, render_ascii, render_svg};
+use std::env;
+use std::fs;
 
 fn main() {
     let filename = "input";
     let all_lines = load(filename);
     let (x1, y1, x2, y2) = load_target_area(&all_lines);
 
+    // --plot dx dy [svg file] draws one launch instead of solving.
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--plot") {
+        let dx: i32 = args[i + 1].parse().unwrap();
+        let dy: i32 = args[i + 2].parse().unwrap();
+        let positions = plot_positions(dx, dy, x1, y1, x2, y2);
+        println!("{}", render_ascii(&positions, x1, y1, x2, y2));
+        if let Some(svg_file) = args.get(i + 3) {
+            fs::write(svg_file, render_svg(&positions, x1, y1, x2, y2)).unwrap();
+        }
+        return;
+    }
+
     let value_a = puzzle_a(x1, y1, x2, y2);
     println!("Solution to 1: {}", value_a);
 
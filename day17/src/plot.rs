@@ -0,0 +1,196 @@
+use std::fmt::Write;
+
+/// Every position of the probe from the launch point until it lands in the target,
+/// or until it is clearly never going to.
+///
+/// ```
+/// let positions = day17::plot_positions(7, 2, 20, -10, 30, -5);
+/// assert_eq!(positions, vec![(0, 0), (7, 2), (13, 3), (18, 3), (22, 2), (25, 0), (27, -3), (28, -7)]);
+///
+/// // Too fast, it flies straight through.
+/// let positions = day17::plot_positions(17, -4, 20, -10, 30, -5);
+/// assert_eq!(positions, vec![(0, 0), (17, -4), (33, -9)]);
+/// ```
+pub fn plot_positions(
+    dx: i32,
+    dy: i32,
+    target_x1: i32,
+    target_y1: i32,
+    target_x2: i32,
+    target_y2: i32,
+) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = (0, 0);
+    let (mut cur_dx, mut cur_dy) = (dx, dy);
+    let mut positions = vec![(x, y)];
+    loop {
+        x += cur_dx;
+        y += cur_dy;
+        cur_dx -= cur_dx.signum();
+        cur_dy -= 1;
+        positions.push((x, y));
+
+        if target_x1 <= x && x <= target_x2 && target_y1 <= y && y <= target_y2 {
+            break;
+        }
+        // Falling and already below it.
+        let below = y < target_y1 && cur_dy < 0;
+        // Past one side and not coming back.
+        let past_right = x > target_x2 && cur_dx >= 0;
+        let past_left = x < target_x1 && cur_dx <= 0;
+        if below || past_right || past_left {
+            break;
+        }
+    }
+    return positions;
+}
+
+// Smallest box holding the launch point, the target and every position.
+fn viewport(
+    positions: &Vec<(i32, i32)>,
+    target_x1: i32,
+    target_y1: i32,
+    target_x2: i32,
+    target_y2: i32,
+) -> (i32, i32, i32, i32) {
+    let mut min_x = target_x1.min(0);
+    let mut max_x = target_x2.max(0);
+    let mut min_y = target_y1.min(0);
+    let mut max_y = target_y2.max(0);
+    for (x, y) in positions.iter() {
+        min_x = min_x.min(*x);
+        max_x = max_x.max(*x);
+        min_y = min_y.min(*y);
+        max_y = max_y.max(*y);
+    }
+    return (min_x, min_y, max_x, max_y);
+}
+
+/// Draw the probe's path like the puzzle does.
+///
+/// `S` is the launch point, `#` the probe after each step and `T` the target.
+/// The view is sized to fit all of them.
+///
+/// ```
+/// let positions = day17::plot_positions(6, 3, 20, -10, 30, -5);
+/// let expected = vec![
+///     "...............#..#............",
+///     "...........#........#..........",
+///     "...............................",
+///     "......#..............#.........",
+///     "...............................",
+///     "...............................",
+///     "S....................#.........",
+///     "...............................",
+///     "...............................",
+///     "...............................",
+///     ".....................#.........",
+///     "....................TTTTTTTTTTT",
+///     "....................TTTTTTTTTTT",
+///     "....................TTTTTTTTTTT",
+///     "....................TTTTTTTTTTT",
+///     "....................T#TTTTTTTTT",
+///     "....................TTTTTTTTTTT",
+/// ];
+/// assert_eq!(day17::render_ascii(&positions, 20, -10, 30, -5), expected.join("\n"));
+/// ```
+pub fn render_ascii(
+    positions: &Vec<(i32, i32)>,
+    target_x1: i32,
+    target_y1: i32,
+    target_x2: i32,
+    target_y2: i32,
+) -> String {
+    let (min_x, min_y, max_x, max_y) =
+        viewport(positions, target_x1, target_y1, target_x2, target_y2);
+    let width = (max_x - min_x + 1) as usize;
+    let height = (max_y - min_y + 1) as usize;
+    let mut grid: Vec<Vec<char>> = vec![vec!['.'; width]; height];
+    // Row 0 is the top, so y has to be flipped.
+    let mut set = |x: i32, y: i32, c: char| {
+        grid[(max_y - y) as usize][(x - min_x) as usize] = c;
+    };
+
+    for y in target_y1..=target_y2 {
+        for x in target_x1..=target_x2 {
+            set(x, y, 'T');
+        }
+    }
+    for (x, y) in positions.iter().skip(1) {
+        set(*x, *y, '#');
+    }
+    set(0, 0, 'S');
+
+    let lines: Vec<String> = grid
+        .into_iter()
+        .map(|row| row.into_iter().collect())
+        .collect();
+    return lines.join("\n");
+}
+
+/// Draw the probe's path as an svg, one unit per grid square.
+///
+/// The target is a filled box, the path a line through every position, and the
+/// launch point a green dot.
+///
+/// ```
+/// let positions = day17::plot_positions(7, 2, 20, -10, 30, -5);
+/// let svg = day17::render_svg(&positions, 20, -10, 30, -5);
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains("viewBox=\"-1 -4 33 16\""));
+/// assert!(svg.contains("points=\"0,0 7,-2 13,-3 18,-3 22,-2 25,0 27,3 28,7\""));
+/// ```
+pub fn render_svg(
+    positions: &Vec<(i32, i32)>,
+    target_x1: i32,
+    target_y1: i32,
+    target_x2: i32,
+    target_y2: i32,
+) -> String {
+    let (min_x, min_y, max_x, max_y) =
+        viewport(positions, target_x1, target_y1, target_x2, target_y2);
+    let mut svg = String::new();
+    // Svg y grows downwards, so everything is drawn at -y. Pad by one on each side.
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+        min_x - 1,
+        -max_y - 1,
+        max_x - min_x + 3,
+        max_y - min_y + 3
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"lightblue\" />",
+        target_x1 as f32 - 0.5,
+        -target_y2 as f32 - 0.5,
+        target_x2 - target_x1 + 1,
+        target_y2 - target_y1 + 1
+    )
+    .unwrap();
+    let points: Vec<String> = positions
+        .iter()
+        .map(|(x, y)| format!("{},{}", x, -y))
+        .collect();
+    writeln!(
+        svg,
+        "  <polyline points=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"0.1\" />",
+        points.join(" ")
+    )
+    .unwrap();
+    for (x, y) in positions.iter().skip(1) {
+        writeln!(
+            svg,
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"0.3\" fill=\"red\" />",
+            x, -y
+        )
+        .unwrap();
+    }
+    writeln!(
+        svg,
+        "  <circle cx=\"0\" cy=\"0\" r=\"0.4\" fill=\"green\" />"
+    )
+    .unwrap();
+    writeln!(svg, "</svg>").unwrap();
+    return svg;
+}
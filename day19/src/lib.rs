@@ -4,6 +4,9 @@ pub use ivec3::{vec3, IVec3};
 pub use rustc_hash::{FxHashMap, FxHashSet};
 use std::cmp::max;
 
+mod map;
+pub use crate::map::{reconstruct_map, BeaconMap, Orientation, ScannerPlacement};
+
 fn rot_x_repeatedly(v: IVec3, n: usize) -> IVec3 {
     let mut result = v;
     for _ in 0..n {
@@ -0,0 +1,427 @@
+use ivec3::{vec3, IVec3};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cmp::max;
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+/// How many beacons two scanners have to share before we trust an alignment.
+const MIN_OVERLAP: usize = 12;
+
+/// One of the 24 rotations a scanner can be facing.
+///
+/// Stored as a 3x3 matrix of rows, so applying it to a scanner relative point
+/// gives the point in the absolute (scanner 0) frame, before translation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub rows: [[i32; 3]; 3],
+}
+
+impl Orientation {
+    /// The orientation that leaves every point where it is.
+    pub fn identity() -> Orientation {
+        return Orientation {
+            rows: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+        };
+    }
+
+    /// Every distinct orientation, with identity first.
+    ///
+    /// ```
+    /// assert_eq!(day19::Orientation::all().len(), 24);
+    /// assert_eq!(day19::Orientation::all()[0], day19::Orientation::identity());
+    /// ```
+    pub fn all() -> Vec<Orientation> {
+        // Same idea as chiral_octahedral_symmetry, but applied to the unit
+        // vectors so we keep the rotation itself rather than just the points.
+        let mut result: Vec<Orientation> = Vec::new();
+        for x_rot in 0..=1 {
+            for y_rot in 0..=3 {
+                for z_rot in 0..=3 {
+                    let rotate = |v: IVec3| {
+                        let mut r = v;
+                        for _ in 0..x_rot {
+                            r = r.rot_x();
+                        }
+                        for _ in 0..y_rot {
+                            r = r.rot_y();
+                        }
+                        for _ in 0..z_rot {
+                            r = r.rot_z();
+                        }
+                        return r;
+                    };
+                    let cx = rotate(vec3(1, 0, 0));
+                    let cy = rotate(vec3(0, 1, 0));
+                    let cz = rotate(vec3(0, 0, 1));
+                    let orientation = Orientation {
+                        rows: [[cx.x, cy.x, cz.x], [cx.y, cy.y, cz.y], [cx.z, cy.z, cz.z]],
+                    };
+                    if !result.contains(&orientation) {
+                        result.push(orientation);
+                    }
+                }
+            }
+        }
+        return result;
+    }
+
+    /// Rotate a point.
+    ///
+    /// ```
+    /// use day19::{vec3, Orientation};
+    /// let o = Orientation { rows: [[0, 1, 0], [-1, 0, 0], [0, 0, 1]] };
+    /// assert_eq!(o.apply(vec3(1, 2, 4)), vec3(1, 2, 4).rot_z());
+    /// ```
+    pub fn apply(&self, v: IVec3) -> IVec3 {
+        let r = &self.rows;
+        return vec3(
+            r[0][0] * v.x + r[0][1] * v.y + r[0][2] * v.z,
+            r[1][0] * v.x + r[1][1] * v.y + r[1][2] * v.z,
+            r[2][0] * v.x + r[2][1] * v.y + r[2][2] * v.z,
+        );
+    }
+
+    fn flat(&self) -> Vec<i32> {
+        return self.rows.iter().flatten().cloned().collect();
+    }
+}
+
+/// Where a scanner ended up once the map was put together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannerPlacement {
+    pub id: usize,
+    /// Position relative to the origin scanner.
+    pub position: IVec3,
+    /// Rotation taking this scanner's readings into the origin frame.
+    pub orientation: Orientation,
+    /// The scanner whose beacons this one was matched against, `None` for the origin.
+    pub aligned_against: Option<usize>,
+}
+
+/// The fully reconstructed map of beacons and scanners.
+///
+/// Everything is in the frame of the lowest numbered scanner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeaconMap {
+    /// Every unique beacon, sorted.
+    pub beacons: Vec<IVec3>,
+    /// Every scanner, sorted by id.
+    pub scanners: Vec<ScannerPlacement>,
+}
+
+/// Put all the scanners together into one map.
+///
+/// Unlike `puzzle_a`, which merges everything into one growing blob, this
+/// matches scanners against each other one at a time so we know which
+/// scanner each alignment came from.
+///
+/// Returns the ids of the scanners that could not be placed, if there are any.
+///
+/// ```
+/// use day19::{vec3, FxHashMap};
+/// let mut scanners = FxHashMap::default();
+/// scanners.insert(0, vec![vec3(1, 2, 3)]);
+/// scanners.insert(1, vec![vec3(4, 5, 6)]);
+/// assert_eq!(day19::reconstruct_map(&scanners), Err(vec![1]));
+/// ```
+pub fn reconstruct_map(
+    scanner_map: &FxHashMap<usize, Vec<IVec3>>,
+) -> Result<BeaconMap, Vec<usize>> {
+    let mut ids: Vec<usize> = scanner_map.keys().cloned().collect();
+    ids.sort();
+    let orientations = Orientation::all();
+
+    let mut placed: FxHashMap<usize, (ScannerPlacement, Vec<IVec3>)> = FxHashMap::default();
+    let mut unplaced: Vec<usize> = ids.clone();
+    let mut to_check: VecDeque<usize> = VecDeque::new();
+    if let Some(&origin) = ids.first() {
+        let placement = ScannerPlacement {
+            id: origin,
+            position: vec3(0, 0, 0),
+            orientation: Orientation::identity(),
+            aligned_against: None,
+        };
+        placed.insert(origin, (placement, scanner_map[&origin].clone()));
+        unplaced.retain(|&id| id != origin);
+        to_check.push_back(origin);
+    }
+
+    while let Some(anchor) = to_check.pop_front() {
+        let anchor_beacons = placed[&anchor].1.clone();
+        let mut still_unplaced: Vec<usize> = Vec::new();
+        for id in unplaced {
+            match align(&anchor_beacons, &scanner_map[&id], &orientations) {
+                Some((position, orientation)) => {
+                    let absolute: Vec<IVec3> = scanner_map[&id]
+                        .iter()
+                        .map(|v| orientation.apply(*v) + position)
+                        .collect();
+                    let placement = ScannerPlacement {
+                        id,
+                        position,
+                        orientation,
+                        aligned_against: Some(anchor),
+                    };
+                    placed.insert(id, (placement, absolute));
+                    to_check.push_back(id);
+                }
+                None => still_unplaced.push(id),
+            }
+        }
+        unplaced = still_unplaced;
+    }
+
+    if !unplaced.is_empty() {
+        return Err(unplaced);
+    }
+
+    let mut beacons: FxHashSet<IVec3> = FxHashSet::default();
+    let mut scanners: Vec<ScannerPlacement> = Vec::new();
+    for id in ids {
+        let (placement, absolute) = placed.remove(&id).unwrap();
+        beacons.extend(absolute);
+        scanners.push(placement);
+    }
+    let mut beacons: Vec<IVec3> = beacons.into_iter().collect();
+    beacons.sort();
+
+    return Ok(BeaconMap { beacons, scanners });
+}
+
+// For each orientation, count how often each offset between an anchor beacon and
+// a rotated beacon shows up. An offset shared by enough pairs is the scanner position.
+fn align(
+    anchor_beacons: &Vec<IVec3>,
+    readings: &Vec<IVec3>,
+    orientations: &Vec<Orientation>,
+) -> Option<(IVec3, Orientation)> {
+    for orientation in orientations {
+        let rotated: Vec<IVec3> = readings.iter().map(|v| orientation.apply(*v)).collect();
+        let mut offsets: FxHashMap<IVec3, usize> = FxHashMap::default();
+        for a in anchor_beacons {
+            for b in rotated.iter() {
+                let count = offsets.entry(*a - *b).or_insert(0);
+                *count += 1;
+                if *count >= MIN_OVERLAP {
+                    return Some((*a - *b, *orientation));
+                }
+            }
+        }
+    }
+    return None;
+}
+
+impl BeaconMap {
+    /// Each (scanner, scanner it was aligned against) pair.
+    pub fn alignment_edges(&self) -> Vec<(usize, usize)> {
+        return self
+            .scanners
+            .iter()
+            .filter_map(|s| s.aligned_against.map(|a| (s.id, a)))
+            .collect();
+    }
+
+    /// Largest manhattan distance between two scanners, the answer to puzzle b.
+    pub fn max_scanner_distance(&self) -> u32 {
+        let mut largest: u32 = 0;
+        for (i, x) in self.scanners.iter().enumerate() {
+            for y in self.scanners.iter().skip(i + 1) {
+                largest = max(largest, x.position.dist_to(&y.position));
+            }
+        }
+        return largest;
+    }
+
+    /// Beacons as csv, one `x,y,z` per line after a header.
+    pub fn beacons_csv(&self) -> String {
+        let mut out = String::from("x,y,z\n");
+        for b in self.beacons.iter() {
+            writeln!(out, "{},{},{}", b.x, b.y, b.z).unwrap();
+        }
+        return out;
+    }
+
+    /// Scanners as csv.
+    ///
+    /// The orientation is written out row by row as `r00` to `r22`, and
+    /// `aligned_against` is left empty for the origin scanner.
+    pub fn scanners_csv(&self) -> String {
+        let mut out =
+            String::from("id,x,y,z,r00,r01,r02,r10,r11,r12,r20,r21,r22,aligned_against\n");
+        for s in self.scanners.iter() {
+            let rotation: Vec<String> =
+                s.orientation.flat().iter().map(|v| v.to_string()).collect();
+            let against = match s.aligned_against {
+                Some(a) => a.to_string(),
+                None => String::new(),
+            };
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                s.id,
+                s.position.x,
+                s.position.y,
+                s.position.z,
+                rotation.join(","),
+                against
+            )
+            .unwrap();
+        }
+        return out;
+    }
+
+    /// The whole map as a json object with `beacons`, `scanners` and `alignments`.
+    pub fn to_json(&self) -> String {
+        let point = |v: &IVec3| format!("[{},{},{}]", v.x, v.y, v.z);
+        let beacons: Vec<String> = self.beacons.iter().map(point).collect();
+        let scanners: Vec<String> = self
+            .scanners
+            .iter()
+            .map(|s| {
+                let rows: Vec<String> = s
+                    .orientation
+                    .rows
+                    .iter()
+                    .map(|r| format!("[{},{},{}]", r[0], r[1], r[2]))
+                    .collect();
+                let against = match s.aligned_against {
+                    Some(a) => a.to_string(),
+                    None => "null".to_string(),
+                };
+                format!(
+                    "{{\"id\":{},\"position\":{},\"orientation\":[{}],\"aligned_against\":{}}}",
+                    s.id,
+                    point(&s.position),
+                    rows.join(","),
+                    against
+                )
+            })
+            .collect();
+        let alignments: Vec<String> = self
+            .alignment_edges()
+            .iter()
+            .map(|(from, to)| format!("{{\"scanner\":{},\"against\":{}}}", from, to))
+            .collect();
+        return format!(
+            "{{\"beacons\":[{}],\"scanners\":[{}],\"alignments\":[{}]}}",
+            beacons.join(","),
+            scanners.join(","),
+            alignments.join(",")
+        );
+    }
+
+    /// An ascii PLY point cloud.
+    ///
+    /// Beacons are white and scanners are red, so they stand out in a viewer.
+    pub fn to_ply(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "ply").unwrap();
+        writeln!(out, "format ascii 1.0").unwrap();
+        writeln!(out, "comment beacons are white, scanners are red").unwrap();
+        writeln!(
+            out,
+            "element vertex {}",
+            self.beacons.len() + self.scanners.len()
+        )
+        .unwrap();
+        for axis in ["x", "y", "z"] {
+            writeln!(out, "property int {}", axis).unwrap();
+        }
+        for colour in ["red", "green", "blue"] {
+            writeln!(out, "property uchar {}", colour).unwrap();
+        }
+        writeln!(out, "end_header").unwrap();
+        for b in self.beacons.iter() {
+            writeln!(out, "{} {} {} 255 255 255", b.x, b.y, b.z).unwrap();
+        }
+        for s in self.scanners.iter() {
+            let p = s.position;
+            writeln!(out, "{} {} {} 255 0 0", p.x, p.y, p.z).unwrap();
+        }
+        return out;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_scanner, split_lines_by_blanks};
+
+    const EXAMPLE: &str = "--- scanner 0 ---\n404,-588,-901\n528,-643,409\n-838,591,734\n390,-675,-793\n-537,-823,-458\n-485,-357,347\n-345,-311,381\n-661,-816,-575\n-876,649,763\n-618,-824,-621\n553,345,-567\n474,580,667\n-447,-329,318\n-584,868,-557\n544,-627,-890\n564,392,-477\n455,729,728\n-892,524,684\n-689,845,-530\n423,-701,434\n7,-33,-71\n630,319,-379\n443,580,662\n-789,900,-551\n459,-707,401\n\n--- scanner 1 ---\n686,422,578\n605,423,415\n515,917,-361\n-336,658,858\n95,138,22\n-476,619,847\n-340,-569,-846\n567,-361,727\n-460,603,-452\n669,-402,600\n729,430,532\n-500,-761,534\n-322,571,750\n-466,-666,-811\n-429,-592,574\n-355,545,-477\n703,-491,-529\n-328,-685,520\n413,935,-424\n-391,539,-444\n586,-435,557\n-364,-763,-893\n807,-499,-711\n755,-354,-619\n553,889,-390\n\n--- scanner 2 ---\n649,640,665\n682,-795,504\n-784,533,-524\n-644,584,-595\n-588,-843,648\n-30,6,44\n-674,560,763\n500,723,-460\n609,671,-379\n-555,-800,653\n-675,-892,-343\n697,-426,-610\n578,704,681\n493,664,-388\n-671,-858,530\n-667,343,800\n571,-461,-707\n-138,-166,112\n-889,563,-600\n646,-828,498\n640,759,510\n-630,509,768\n-681,-892,-333\n673,-379,-804\n-742,-814,-386\n577,-820,562\n\n--- scanner 3 ---\n-589,542,597\n605,-692,669\n-500,565,-823\n-660,373,557\n-458,-679,-417\n-488,449,543\n-626,468,-788\n338,-750,-386\n528,-832,-391\n562,-778,733\n-938,-730,414\n543,643,-506\n-524,371,-870\n407,773,750\n-104,29,83\n378,-903,-323\n-778,-728,485\n426,699,580\n-438,-605,-362\n-469,-447,-387\n509,732,623\n647,635,-688\n-868,-804,481\n614,-800,639\n595,780,-596\n\n--- scanner 4 ---\n727,592,562\n-293,-554,779\n441,611,-461\n-714,465,-776\n-743,427,-804\n-660,-479,-426\n832,-632,460\n927,-485,-438\n408,393,-506\n466,436,-512\n110,16,151\n-258,-428,682\n-393,719,612\n-211,-452,876\n808,-476,-593\n-575,615,604\n-485,667,467\n-680,325,-822\n-627,-443,-432\n872,-547,-609\n833,512,582\n807,604,487\n839,-516,451\n891,-625,532\n-652,-548,-490\n30,-46,-14";
+
+    fn example_map() -> BeaconMap {
+        let split = split_lines_by_blanks(EXAMPLE);
+        let scanner_map: FxHashMap<usize, Vec<IVec3>> =
+            split.iter().map(|x| parse_scanner(x)).collect();
+        return reconstruct_map(&scanner_map).unwrap();
+    }
+
+    #[test]
+    fn test_reconstruct_map() {
+        let map = example_map();
+        assert_eq!(map.beacons.len(), 79);
+        assert_eq!(map.max_scanner_distance(), 3621);
+        let positions: Vec<IVec3> = map.scanners.iter().map(|s| s.position).collect();
+        assert_eq!(
+            positions,
+            vec![
+                vec3(0, 0, 0),
+                vec3(68, -1246, -43),
+                vec3(1105, -1205, 1229),
+                vec3(-92, -2380, -20),
+                vec3(-20, -1133, 1061)
+            ]
+        );
+        assert!(map.beacons.contains(&vec3(-892, 524, 684)));
+    }
+
+    #[test]
+    fn test_alignment_edges() {
+        let map = example_map();
+        // From the puzzle text, 1 lines up with 0, 4 with 1, and 2 with 4.
+        let edges = map.alignment_edges();
+        assert_eq!(edges.len(), 4);
+        assert!(edges.contains(&(1, 0)));
+        assert!(edges.contains(&(4, 1)));
+        assert!(edges.contains(&(2, 4)));
+    }
+
+    #[test]
+    fn test_orientation_maps_readings() {
+        let map = example_map();
+        let split = split_lines_by_blanks(EXAMPLE);
+        let (_, readings) = parse_scanner(&split[1]);
+        let scanner = &map.scanners[1];
+        for r in readings {
+            let absolute = scanner.orientation.apply(r) + scanner.position;
+            assert!(map.beacons.contains(&absolute));
+        }
+    }
+
+    #[test]
+    fn test_exports() {
+        let map = example_map();
+        let csv = map.beacons_csv();
+        assert_eq!(csv.lines().count(), 80);
+        assert_eq!(csv.lines().next(), Some("x,y,z"));
+
+        let scanners = map.scanners_csv();
+        assert_eq!(scanners.lines().nth(1), Some("0,0,0,0,1,0,0,0,1,0,0,0,1,"));
+        assert!(scanners
+            .lines()
+            .nth(2)
+            .unwrap()
+            .starts_with("1,68,-1246,-43,"));
+        assert!(scanners.lines().nth(2).unwrap().ends_with(",0"));
+
+        let json = map.to_json();
+        assert!(json.starts_with("{\"beacons\":[["));
+        assert!(json.contains("{\"scanner\":1,\"against\":0}"));
+        assert!(json.contains("\"aligned_against\":null"));
+
+        let ply = map.to_ply();
+        assert!(ply.contains("element vertex 84\n"));
+        assert_eq!(ply.lines().count(), 11 + 84);
+        assert!(ply.ends_with("-20 -1133 1061 255 0 0\n"));
+    }
+}
@@ -0,0 +1,32 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use day21::{count_quantum_wins, count_quantum_wins_dp, GameRules};
+
+fn game(board_size: u32, winning_score: u32) -> GameRules {
+    let mut rules = GameRules::quantum();
+    rules.board_size = board_size;
+    rules.winning_score = winning_score;
+    return rules;
+}
+
+fn quantum_solvers(c: &mut Criterion) {
+    let games = [
+        ("puzzle", GameRules::quantum(), vec![4, 8]),
+        ("board_10_to_50", game(10, 50), vec![4, 8]),
+        ("board_20_to_40", game(20, 40), vec![4, 8]),
+    ];
+
+    let mut group = c.benchmark_group("quantum");
+    group.sample_size(10);
+    for (name, rules, start) in games.iter() {
+        group.bench_with_input(BenchmarkId::new("memo", name), rules, |b, rules| {
+            b.iter(|| count_quantum_wins(black_box(rules), black_box(start)))
+        });
+        group.bench_with_input(BenchmarkId::new("dp", name), rules, |b, rules| {
+            b.iter(|| count_quantum_wins_dp(black_box(rules), black_box(start)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, quantum_solvers);
+criterion_main!(benches);
@@ -1,9 +1,96 @@
 pub use filelib::load;
 pub use rustc_hash::FxHashMap;
 
-type CacheKey = Vec<u32>;
-type CacheValue = Vec<u128>;
-type Cache = FxHashMap<CacheKey, CacheValue>;
+mod quantum;
+pub use crate::quantum::{count_quantum_wins, count_quantum_wins_dp, MAX_PLAYERS};
+
+/// Everything that decides how a game of Dirac Dice plays out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRules {
+    /// Faces on the die, numbered 1 to `die_sides`.
+    pub die_sides: u32,
+    /// How many times the die is rolled (and summed) each turn.
+    pub rolls_per_turn: u32,
+    /// Spaces on the circular track, numbered 1 to `board_size`.
+    pub board_size: u32,
+    /// First player to reach this score wins.
+    pub winning_score: u32,
+    pub num_players: usize,
+}
+
+impl GameRules {
+    /// The rules from part 1, a 100 sided deterministic die played to 1000.
+    pub fn deterministic() -> Self {
+        return Self {
+            die_sides: 100,
+            rolls_per_turn: 3,
+            board_size: 10,
+            winning_score: 1000,
+            num_players: 2,
+        };
+    }
+
+    /// The rules from part 2, a 3 sided Dirac die played to 21.
+    pub fn quantum() -> Self {
+        return Self {
+            die_sides: 3,
+            rolls_per_turn: 3,
+            board_size: 10,
+            winning_score: 21,
+            num_players: 2,
+        };
+    }
+
+    /// Every total a turn can roll, along with how many universes roll it.
+    ///
+    /// ```
+    /// let freqs = day21::GameRules::quantum().roll_frequencies();
+    /// assert_eq!(freqs, vec![(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]);
+    /// ```
+    pub fn roll_frequencies(&self) -> Vec<(u32, u128)> {
+        let mut freqs: Vec<u128> = vec![1];
+        for _ in 0..self.rolls_per_turn {
+            let mut next: Vec<u128> = vec![0; freqs.len() + self.die_sides as usize];
+            for (total, count) in freqs.iter().enumerate() {
+                for face in 1..=self.die_sides as usize {
+                    next[total + face] += count;
+                }
+            }
+            freqs = next;
+        }
+        return freqs
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .map(|(total, count)| (total as u32, count))
+            .collect();
+    }
+
+    fn check_start(&self, start: &Vec<u32>) -> Result<(), String> {
+        if start.len() != self.num_players {
+            return Err(format!(
+                "{} start positions for {} players",
+                start.len(),
+                self.num_players
+            ));
+        }
+        for pos in start {
+            if *pos < 1 || *pos > self.board_size {
+                return Err(format!("Start position {} is off the board", pos));
+            }
+        }
+        return Ok(());
+    }
+}
+
+/// How a game on the deterministic die ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeterministicOutcome {
+    pub scores: Vec<u32>,
+    pub num_rolls: u32,
+    /// One entry per player, 1 for the winner and 0 for everyone else.
+    pub wins: Vec<u128>,
+}
 
 #[derive(Debug)]
 struct DeterministicDie {
@@ -30,11 +117,12 @@ impl DeterministicDie {
     }
 }
 
-fn roll_three_times(die: &mut DeterministicDie) -> u32 {
-    let roll_one = die.roll();
-    let roll_two = die.roll();
-    let roll_three = die.roll();
-    return roll_one + roll_two + roll_three;
+fn roll_for_turn(die: &mut DeterministicDie, rolls: u32) -> u32 {
+    let mut total = 0;
+    for _ in 0..rolls {
+        total += die.roll();
+    }
+    return total;
 }
 
 #[derive(Debug)]
@@ -44,20 +132,22 @@ struct State<'a> {
     cur_turn: usize,
     player_score: Vec<u32>,
     die: &'a mut DeterministicDie,
+    rolls_per_turn: u32,
     victory: u32,
 }
 
 impl<'a> State<'a> {
-    fn new(num_players: usize, max_pos: u32, die: &'a mut DeterministicDie) -> Self {
-        let players = vec![0; num_players];
-        let players_score = vec![0; num_players];
+    fn new(rules: &GameRules, die: &'a mut DeterministicDie) -> Self {
+        let players = vec![0; rules.num_players];
+        let players_score = vec![0; rules.num_players];
         return Self {
-            max_pos: max_pos,
+            max_pos: rules.board_size,
             player_pos: players,
             cur_turn: 0,
             player_score: players_score,
             die: die,
-            victory: 1000,
+            rolls_per_turn: rules.rolls_per_turn,
+            victory: rules.winning_score,
         };
     }
 
@@ -66,7 +156,7 @@ impl<'a> State<'a> {
     }
 
     fn turn(&mut self) {
-        let move_pos = roll_three_times(self.die);
+        let move_pos = roll_for_turn(self.die, self.rolls_per_turn);
         let start_pos = self.player_pos[self.cur_turn];
         let mut new_pos: u32 = move_pos + start_pos;
         while new_pos > self.max_pos {
@@ -83,76 +173,12 @@ impl<'a> State<'a> {
         return self.die.num_rolls;
     }
 
-    fn get_lowest_score(&self) -> u32 {
-        return *self.player_score.iter().min().unwrap();
-    }
-
-    fn game_is_end(&self) -> bool {
-        let winning_players: Vec<&u32> = self
+    fn get_winner(&self) -> usize {
+        return self
             .player_score
             .iter()
-            .filter(|&x| *x >= self.victory)
-            .collect();
-        return winning_players.len() >= 1;
-    }
-}
-
-#[derive(Debug, Clone)]
-struct QuantumState {
-    max_pos: u32,
-    player_pos: Vec<u32>,
-    cur_turn: usize,
-    player_score: Vec<u32>,
-    victory: u32,
-}
-
-impl QuantumState {
-    fn new(num_players: usize, max_pos: u32) -> Self {
-        let players = vec![0; num_players];
-        let players_score = vec![0; num_players];
-        return Self {
-            max_pos: max_pos,
-            player_pos: players,
-            cur_turn: 0,
-            player_score: players_score,
-            victory: 21,
-        };
-    }
-
-    fn set_start_position(&mut self, pos: &Vec<u32>) {
-        self.player_pos = pos.clone();
-    }
-
-    fn turn(&self) -> Vec<QuantumState> {
-        // So if we roll a 3 sided die 3 times, we have 27 realities
-        let mut universes: Vec<QuantumState> = Vec::new();
-        for first_roll_value in 1..=3 {
-            for second_roll_value in 1..=3 {
-                for third_roll_value in 1..=3 {
-                    let mut new = self.clone();
-                    new.advance_by_roll(first_roll_value + second_roll_value + third_roll_value);
-                    // Ensure the Vecs are being copied, that could maybe cause my mystery state...
-                    assert_ne!(self.player_pos, new.player_pos);
-                    assert_ne!(self.player_score, new.player_score);
-                    universes.push(new);
-                }
-            }
-        }
-        assert_eq!(universes.len(), 27);
-        return universes;
-    }
-
-    fn advance_by_roll(&mut self, roll: usize) {
-        let move_pos: u32 = roll as u32;
-        let start_pos = self.player_pos[self.cur_turn];
-        let mut new_pos: u32 = move_pos + start_pos;
-        while new_pos > self.max_pos {
-            new_pos -= self.max_pos;
-        }
-        self.player_pos[self.cur_turn] = new_pos;
-        self.player_score[self.cur_turn] += new_pos as u32;
-        self.cur_turn += 1;
-        self.cur_turn = self.cur_turn % self.player_score.len();
+            .position(|&x| x >= self.victory)
+            .unwrap();
     }
 
     fn game_is_end(&self) -> bool {
@@ -163,23 +189,6 @@ impl QuantumState {
             .collect();
         return winning_players.len() >= 1;
     }
-
-    fn get_winner(&self) -> usize {
-        for (i, score) in self.player_score.iter().enumerate() {
-            if *score >= self.victory {
-                return i;
-            }
-        }
-        return usize::MAX;
-    }
-
-    fn to_cache_key(&self) -> CacheKey {
-        let mut cache_key = Vec::new();
-        cache_key.push(self.cur_turn as u32); // The cache must tell turns apart.
-        cache_key.append(&mut self.player_pos.clone());
-        cache_key.append(&mut self.player_score.clone());
-        return cache_key;
-    }
 }
 
 /// Parse the input format, such that player 1 is at index 0, 2 is at index 1, etc.
@@ -205,43 +214,42 @@ pub fn parse_player_pos(input: &str) -> Vec<u32> {
 /// assert_eq!(day21::puzzle_a(&player_pos), 739785);
 /// ```
 pub fn puzzle_a(player_pos: &Vec<u32>) -> u32 {
-    let mut die = DeterministicDie::new(100);
-    let mut state = State::new(player_pos.len(), 10, &mut die);
+    let outcome = play_deterministic(&GameRules::deterministic(), player_pos).unwrap();
+    let low_score = *outcome.scores.iter().min().unwrap();
+    return outcome.num_rolls * low_score;
+}
+
+/// Play one game with a die that rolls 1, 2, 3, ... wrapping back to 1.
+///
+/// Gives an Err if there isn't one start position on the board per player.
+///
+/// ```
+/// use day21::{play_deterministic, GameRules};
+/// let outcome = play_deterministic(&GameRules::deterministic(), &vec![4, 8]).unwrap();
+/// assert_eq!(outcome.scores, vec![1000, 745]);
+/// assert_eq!(outcome.num_rolls, 993);
+/// assert_eq!(outcome.wins, vec![1, 0]);
+/// assert!(play_deterministic(&GameRules::deterministic(), &vec![4, 11]).is_err());
+/// assert!(play_deterministic(&GameRules::deterministic(), &vec![4]).is_err());
+/// ```
+pub fn play_deterministic(
+    rules: &GameRules,
+    player_pos: &Vec<u32>,
+) -> Result<DeterministicOutcome, String> {
+    rules.check_start(player_pos)?;
+    let mut die = DeterministicDie::new(rules.die_sides);
+    let mut state = State::new(rules, &mut die);
     state.set_start_position(player_pos);
     while !state.game_is_end() {
         state.turn();
     }
-    let rolls = state.get_num_rolls();
-    let low_score = state.get_lowest_score();
-    return rolls * low_score;
-}
-
-fn run_one_quantum_game(state: &QuantumState, cache: &mut Cache) -> CacheValue {
-    // If its in our cache, don't do all this again.
-    if let Some(score) = cache.get(&state.to_cache_key()) {
-        return score.clone();
-    }
-
-    let mut score: CacheValue = vec![0; state.player_pos.len()];
-    // First check if this game is already done, if so toss it.
-    if state.game_is_end() {
-        let winner = state.get_winner();
-        score[winner] += 1;
-        return score;
-    }
-
-    // Recursively run the sub universes
-    let new_universes = state.turn();
-    for universe in new_universes.iter() {
-        let winners = run_one_quantum_game(&universe, cache);
-        for (i, v) in winners.iter().enumerate() {
-            score[i] += v;
-        }
-    }
-
-    // Insert into the cache before we leave, so we don't have to play these ones again.
-    cache.insert(state.to_cache_key(), score.clone());
-    return score;
+    let mut wins = vec![0; rules.num_players];
+    wins[state.get_winner()] = 1;
+    return Ok(DeterministicOutcome {
+        scores: state.player_score.clone(),
+        num_rolls: state.get_num_rolls(),
+        wins: wins,
+    });
 }
 
 /// Run the quantum game and return the higher number of wins.
@@ -254,10 +262,7 @@ fn run_one_quantum_game(state: &QuantumState, cache: &mut Cache) -> CacheValue {
 /// assert_eq!(day21::puzzle_b(&player_pos), 444356092776315);
 /// ```
 pub fn puzzle_b(player_pos: &Vec<u32>) -> u128 {
-    let mut start_state = QuantumState::new(player_pos.len(), 10);
-    start_state.set_start_position(&player_pos);
-    let mut cache: Cache = FxHashMap::default();
-    let result = run_one_quantum_game(&start_state, &mut cache);
+    let result = count_quantum_wins(&GameRules::quantum(), player_pos).unwrap();
     return *result.iter().max().unwrap();
 }
 
@@ -270,7 +275,7 @@ mod tests {
     }
 
     fn get_state(die: &mut DeterministicDie) -> State {
-        let mut state = State::new(2, 10, die);
+        let mut state = State::new(&GameRules::deterministic(), die);
         state.set_start_position(&vec![4, 8]);
         return state;
     }
@@ -307,4 +312,43 @@ mod tests {
         assert_eq!(state.player_pos, vec![6, 6]);
         assert_eq!(state.player_score, vec![26, 22]);
     }
+
+    #[test]
+    fn test_three_player_deterministic() {
+        let mut rules = GameRules::deterministic();
+        rules.num_players = 3;
+        rules.winning_score = 20;
+        // Player one moves 6, 33 then 60 spaces, landing on 7, 10 and 10.
+        let outcome = play_deterministic(&rules, &vec![1, 1, 1]).unwrap();
+        assert_eq!(outcome.scores, vec![27, 14, 11]);
+        assert_eq!(outcome.num_rolls, 21);
+        assert_eq!(outcome.wins, vec![1, 0, 0]);
+    }
+
+    #[test]
+    fn test_roll_frequencies_sum_to_all_universes() {
+        let mut rules = GameRules::quantum();
+        rules.die_sides = 4;
+        rules.rolls_per_turn = 2;
+        let freqs = rules.roll_frequencies();
+        assert_eq!(freqs.first(), Some(&(2, 1)));
+        assert_eq!(freqs.last(), Some(&(8, 1)));
+        assert_eq!(freqs.iter().map(|(_, c)| c).sum::<u128>(), 16);
+    }
+
+    #[test]
+    fn test_quantum_single_roll_to_one() {
+        // With a 2 sided die rolled once and a target of 1, player one always wins.
+        let rules = GameRules {
+            die_sides: 2,
+            rolls_per_turn: 1,
+            board_size: 10,
+            winning_score: 1,
+            num_players: 3,
+        };
+        assert_eq!(
+            count_quantum_wins(&rules, &vec![1, 2, 3]),
+            Ok(vec![2, 0, 0])
+        );
+    }
 }
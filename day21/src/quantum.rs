@@ -0,0 +1,330 @@
+use crate::GameRules;
+use rustc_hash::FxHashMap;
+
+/// Most players the quantum solvers have room for.
+pub const MAX_PLAYERS: usize = 4;
+
+type Wins = [u128; MAX_PLAYERS];
+type Cache = FxHashMap<u128, Wins>;
+
+/// How a whole quantum game state is squeezed into one `u128`.
+///
+/// From the least significant bits up: whose turn it is, then each player's
+/// position (stored 0 based), then each player's score. Only games still in
+/// progress get packed, so scores never reach the winning score.
+#[derive(Debug, Clone, Copy)]
+struct StateLayout {
+    num_players: usize,
+    turn_bits: u32,
+    pos_bits: u32,
+    score_bits: u32,
+}
+
+// Bits needed to store every value in 0..values
+fn bits_for(values: u32) -> u32 {
+    return 32 - (values.max(2) - 1).leading_zeros();
+}
+
+fn mask(bits: u32) -> u128 {
+    return (1 << bits) - 1;
+}
+
+// Games nobody can ever win, or with nobody to win them, have no answer.
+fn check_winnable(rules: &GameRules) -> Result<(), String> {
+    if rules.num_players == 0 {
+        return Err("Somebody has to play".to_string());
+    }
+    if rules.winning_score == 0 {
+        return Err("Somebody has to be able to win".to_string());
+    }
+    return Ok(());
+}
+
+fn too_many_universes() -> String {
+    return "Too many universes to count in a u128".to_string();
+}
+
+impl StateLayout {
+    fn new(rules: &GameRules) -> Result<Self, String> {
+        check_winnable(rules)?;
+        if rules.num_players > MAX_PLAYERS {
+            return Err(format!(
+                "Quantum games support 1 to {} players, not {}",
+                MAX_PLAYERS, rules.num_players
+            ));
+        }
+        let layout = Self {
+            num_players: rules.num_players,
+            turn_bits: bits_for(rules.num_players as u32),
+            pos_bits: bits_for(rules.board_size),
+            score_bits: bits_for(rules.winning_score),
+        };
+        let total =
+            layout.turn_bits + layout.num_players as u32 * (layout.pos_bits + layout.score_bits);
+        if total > 128 {
+            return Err("Game is too large to pack into 128 bits".to_string());
+        }
+        return Ok(layout);
+    }
+
+    fn pos_shift(&self, player: usize) -> u32 {
+        return self.turn_bits + player as u32 * self.pos_bits;
+    }
+
+    fn score_shift(&self, player: usize) -> u32 {
+        return self.turn_bits
+            + self.num_players as u32 * self.pos_bits
+            + player as u32 * self.score_bits;
+    }
+
+    fn pack(&self, turn: usize, positions: &[u32], scores: &[u32]) -> u128 {
+        let mut key = turn as u128;
+        for player in 0..self.num_players {
+            key |= ((positions[player] - 1) as u128) << self.pos_shift(player);
+            key |= (scores[player] as u128) << self.score_shift(player);
+        }
+        return key;
+    }
+
+    fn turn(&self, key: u128) -> usize {
+        return (key & mask(self.turn_bits)) as usize;
+    }
+
+    fn position(&self, key: u128, player: usize) -> u32 {
+        return ((key >> self.pos_shift(player)) & mask(self.pos_bits)) as u32 + 1;
+    }
+
+    fn score(&self, key: u128, player: usize) -> u32 {
+        return ((key >> self.score_shift(player)) & mask(self.score_bits)) as u32;
+    }
+
+    // Move the current player and hand the turn to the next one.
+    fn advance(&self, key: u128, new_pos: u32, new_score: u32) -> u128 {
+        let player = self.turn(key);
+        let next_turn = (player + 1) % self.num_players;
+        let mut next = key & !mask(self.turn_bits);
+        next &= !(mask(self.pos_bits) << self.pos_shift(player));
+        next &= !(mask(self.score_bits) << self.score_shift(player));
+        next |= next_turn as u128;
+        next |= ((new_pos - 1) as u128) << self.pos_shift(player);
+        next |= (new_score as u128) << self.score_shift(player);
+        return next;
+    }
+}
+
+fn run_one_quantum_game(
+    layout: &StateLayout,
+    rules: &GameRules,
+    roll_frequencies: &Vec<(u32, u128)>,
+    key: u128,
+    cache: &mut Cache,
+) -> Option<Wins> {
+    // If its in our cache, don't do all this again.
+    if let Some(wins) = cache.get(&key) {
+        return Some(*wins);
+    }
+
+    let mut wins: Wins = [0; MAX_PLAYERS];
+    let player = layout.turn(key);
+    let pos = layout.position(key, player);
+    let score = layout.score(key, player);
+    for (roll, count) in roll_frequencies.iter() {
+        let new_pos = (pos - 1 + roll) % rules.board_size + 1;
+        let new_score = score + new_pos;
+        // A win ends that universe, so there is nothing to recurse into.
+        if new_score >= rules.winning_score {
+            wins[player] = wins[player].checked_add(*count)?;
+            continue;
+        }
+        let next = layout.advance(key, new_pos, new_score);
+        let sub_wins = run_one_quantum_game(layout, rules, roll_frequencies, next, cache)?;
+        for (i, v) in sub_wins.iter().enumerate() {
+            wins[i] = wins[i].checked_add(v.checked_mul(*count)?)?;
+        }
+    }
+
+    // Insert into the cache before we leave, so we don't have to play these ones again.
+    cache.insert(key, wins);
+    return Some(wins);
+}
+
+/// Split the universe on every roll, and count how many universes each player wins in.
+///
+/// This recurses from the start and memoises on a packed `u128` state. Gives an Err if
+/// the start positions don't fit the rules, the game can't be won, there are more than
+/// `MAX_PLAYERS` players, the state doesn't pack into 128 bits, or the counts overflow.
+///
+/// ```
+/// use day21::{count_quantum_wins, GameRules};
+/// let rules = GameRules::quantum();
+/// let wins = count_quantum_wins(&rules, &vec![4, 8]);
+/// assert_eq!(wins, Ok(vec![444356092776315, 341960390180808]));
+/// assert!(count_quantum_wins(&rules, &vec![0, 8]).is_err());
+///
+/// let mut five_players = GameRules::quantum();
+/// five_players.num_players = 5;
+/// assert!(count_quantum_wins(&five_players, &vec![1, 2, 3, 4, 5]).is_err());
+/// let mut unwinnable = GameRules::quantum();
+/// unwinnable.winning_score = 0;
+/// assert!(count_quantum_wins(&unwinnable, &vec![4, 8]).is_err());
+/// let mut huge = GameRules::quantum();
+/// (huge.board_size, huge.winning_score, huge.num_players) = (u32::MAX, u32::MAX, 4);
+/// assert!(count_quantum_wins(&huge, &vec![1, 2, 3, 4]).is_err());
+///
+/// // A d100 rolled ten times a turn splits into 10^20 universes every turn.
+/// let mut many = GameRules::quantum();
+/// (many.die_sides, many.rolls_per_turn, many.winning_score) = (100, 10, 30);
+/// assert!(count_quantum_wins(&many, &vec![4, 8]).is_err());
+/// ```
+pub fn count_quantum_wins(rules: &GameRules, player_pos: &Vec<u32>) -> Result<Vec<u128>, String> {
+    rules.check_start(player_pos)?;
+    let layout = StateLayout::new(rules)?;
+    let start = layout.pack(0, player_pos, &vec![0; rules.num_players]);
+    let mut cache: Cache = FxHashMap::default();
+    let wins = run_one_quantum_game(&layout, rules, &rules.roll_frequencies(), start, &mut cache)
+        .ok_or_else(too_many_universes)?;
+    return Ok(wins[..rules.num_players].to_vec());
+}
+
+/// Same as `count_quantum_wins`, but fills a table forwards instead of recursing.
+///
+/// The table has a slot for every (scores, positions, turn) and holds how many
+/// universes are in that state. Scores are the most significant part of the index
+/// and every move raises a score, so universes only ever flow to later slots, and
+/// one pass from the start is enough. Gives an Err if the start positions don't fit the
+/// rules, the game can't be won, the table would be too large to index, or the counts
+/// overflow.
+///
+/// ```
+/// use day21::{count_quantum_wins_dp, GameRules};
+/// let rules = GameRules::quantum();
+/// let wins = count_quantum_wins_dp(&rules, &vec![4, 8]);
+/// assert_eq!(wins, Ok(vec![444356092776315, 341960390180808]));
+/// assert!(count_quantum_wins_dp(&rules, &vec![4, 8, 1]).is_err());
+///
+/// let mut unwinnable = GameRules::quantum();
+/// unwinnable.winning_score = 0;
+/// assert!(count_quantum_wins_dp(&unwinnable, &vec![4, 8]).is_err());
+/// let mut huge = GameRules::quantum();
+/// (huge.board_size, huge.winning_score, huge.num_players) = (u32::MAX, u32::MAX, 4);
+/// assert!(count_quantum_wins_dp(&huge, &vec![1, 2, 3, 4]).is_err());
+///
+/// let mut many = GameRules::quantum();
+/// (many.die_sides, many.rolls_per_turn, many.winning_score) = (100, 10, 30);
+/// assert!(count_quantum_wins_dp(&many, &vec![4, 8]).is_err());
+/// ```
+pub fn count_quantum_wins_dp(
+    rules: &GameRules,
+    player_pos: &Vec<u32>,
+) -> Result<Vec<u128>, String> {
+    rules.check_start(player_pos)?;
+    check_winnable(rules)?;
+    let players = rules.num_players;
+    let board = rules.board_size as usize;
+    let target = rules.winning_score as usize;
+    let too_large = || "Game is too large for a table".to_string();
+    let pos_states = board.checked_pow(players as u32).ok_or_else(too_large)?;
+    let table_size = target
+        .checked_pow(players as u32)
+        .and_then(|s| s.checked_mul(pos_states))
+        .and_then(|s| s.checked_mul(players))
+        .ok_or_else(too_large)?;
+
+    let mut table: Vec<u128> = vec![0; table_size];
+    let mut start_pos_index = 0;
+    for (i, pos) in player_pos.iter().enumerate() {
+        start_pos_index += (*pos as usize - 1) * board.pow(i as u32);
+    }
+    // Scores and turn start at 0, so they add nothing to the index.
+    let start = start_pos_index * players;
+    table[start] = 1;
+
+    let roll_frequencies = rules.roll_frequencies();
+    let mut wins: Vec<u128> = vec![0; players];
+    for index in start..table_size {
+        let universes = table[index];
+        if universes == 0 {
+            continue;
+        }
+        let turn = index % players;
+        let pos_index = (index / players) % pos_states;
+        let score_index = index / players / pos_states;
+        let pos_place = board.pow(turn as u32);
+        let score_place = target.pow(turn as u32);
+        let pos = (pos_index / pos_place) % board;
+        let score = (score_index / score_place) % target;
+        let next_turn = (turn + 1) % players;
+
+        for (roll, count) in roll_frequencies.iter() {
+            let new_pos = (pos + *roll as usize) % board;
+            let new_score = score + new_pos + 1;
+            let split = universes
+                .checked_mul(*count)
+                .ok_or_else(too_many_universes)?;
+            if new_score >= target {
+                wins[turn] = wins[turn]
+                    .checked_add(split)
+                    .ok_or_else(too_many_universes)?;
+                continue;
+            }
+            let new_pos_index = pos_index - pos * pos_place + new_pos * pos_place;
+            let new_score_index = score_index + (new_score - score) * score_place;
+            let slot =
+                &mut table[(new_score_index * pos_states + new_pos_index) * players + next_turn];
+            *slot = slot.checked_add(split).ok_or_else(too_many_universes)?;
+        }
+    }
+    return Ok(wins);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_round_trip() {
+        let mut rules = GameRules::quantum();
+        rules.num_players = 3;
+        let layout = StateLayout::new(&rules).unwrap();
+        let key = layout.pack(2, &[10, 1, 7], &[20, 0, 13]);
+        assert_eq!(layout.turn(key), 2);
+        assert_eq!(layout.position(key, 0), 10);
+        assert_eq!(layout.position(key, 1), 1);
+        assert_eq!(layout.position(key, 2), 7);
+        assert_eq!(layout.score(key, 0), 20);
+        assert_eq!(layout.score(key, 2), 13);
+
+        let next = layout.advance(key, 3, 16);
+        assert_eq!(layout.turn(next), 0);
+        assert_eq!(layout.position(next, 2), 3);
+        assert_eq!(layout.score(next, 2), 16);
+        assert_eq!(layout.position(next, 0), 10);
+        assert_eq!(layout.score(next, 0), 20);
+    }
+
+    #[test]
+    fn test_memo_and_dp_agree() {
+        let mut big_board = GameRules::quantum();
+        big_board.board_size = 17;
+        big_board.winning_score = 40;
+        let mut three_players = GameRules::quantum();
+        three_players.num_players = 3;
+        three_players.winning_score = 15;
+        let mut d6 = GameRules::quantum();
+        d6.die_sides = 6;
+        d6.rolls_per_turn = 2;
+
+        let games = vec![
+            (GameRules::quantum(), vec![4, 8]),
+            (big_board, vec![17, 1]),
+            (three_players, vec![1, 5, 9]),
+            (d6, vec![3, 3]),
+        ];
+        for (rules, start) in games {
+            assert_eq!(
+                count_quantum_wins(&rules, &start),
+                count_quantum_wins_dp(&rules, &start)
+            );
+        }
+    }
+}
@@ -0,0 +1,725 @@
+// A burrow that isn't tied to the puzzle's shape.
+// Same movement rules as State<R>, but the hallway length, the rooms, the species and their
+// energy costs all come from the diagram (and the caller) instead of being baked in.
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt::{Display, Formatter};
+
+use rustc_hash::FxHashMap;
+
+/// The fixed parts of a burrow: its shape, and who lives where.
+///
+/// Species are named by the letters in the diagram. Sorted alphabetically, the first species
+/// belongs in the leftmost room, the second in the next room, and so on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Burrow {
+    hallway_len: usize,
+    room_x: Vec<usize>,
+    room_depth: usize,
+    species: Vec<char>,
+    energy: Vec<usize>,
+}
+
+/// Where every amphipod is.
+///
+/// Each space holds 0 when empty, or the species index + 1. The hallway comes first, then each
+/// room from left to right, top to bottom.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BurrowState {
+    spaces: Vec<u8>,
+}
+
+/// A space in the burrow. Hallway spaces count from the left, room depth counts down from the top.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Location {
+    Hallway(usize),
+    Room { room: usize, depth: usize },
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Location::Hallway(x) => write!(f, "hallway {}", x),
+            Location::Room { room, depth } => write!(f, "room {} depth {}", room, depth),
+        };
+    }
+}
+
+/// One amphipod moving, and what it cost.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BurrowMove {
+    pub species: char,
+    pub from: Location,
+    pub to: Location,
+    pub energy: usize,
+}
+
+impl Display for BurrowMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            f,
+            "{} moves from {} to {} for {} energy",
+            self.species, self.from, self.to, self.energy
+        );
+    }
+}
+
+/// The cheapest way to organise a burrow.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BurrowSolution {
+    pub energy: usize,
+    pub moves: Vec<BurrowMove>,
+    /// The starting state, then the state after each move.
+    pub states: Vec<BurrowState>,
+}
+
+// A single move out of a state, by space index.
+struct Transition {
+    state: BurrowState,
+    energy: usize,
+    from: usize,
+    to: usize,
+}
+
+impl Burrow {
+    pub fn hallway_len(&self) -> usize {
+        return self.hallway_len;
+    }
+
+    pub fn num_rooms(&self) -> usize {
+        return self.room_x.len();
+    }
+
+    pub fn room_depth(&self) -> usize {
+        return self.room_depth;
+    }
+
+    /// Species letters, in the order of the rooms they belong in.
+    pub fn species(&self) -> &Vec<char> {
+        return &self.species;
+    }
+
+    /// Energy per step of the given species, None if it isn't in this burrow.
+    pub fn energy(&self, species: char) -> Option<usize> {
+        return self
+            .species
+            .iter()
+            .position(|&s| s == species)
+            .map(|i| self.energy[i]);
+    }
+
+    /// Change how much energy a single step costs a species.
+    ///
+    /// By default the first species costs 1, the next 10, then 100 and so on.
+    ///
+    /// ```
+    /// let diagram = "#######\n#.....#\n###B#A#\n  #####";
+    /// let (mut burrow, _) = day23::parse_burrow(diagram).unwrap();
+    /// assert_eq!(burrow.energy('B'), Some(10));
+    /// burrow.set_energy('B', 3).unwrap();
+    /// assert_eq!(burrow.energy('B'), Some(3));
+    /// assert!(burrow.set_energy('Z', 3).is_err());
+    /// ```
+    pub fn set_energy(&mut self, species: char, energy: usize) -> Result<(), String> {
+        match self.species.iter().position(|&s| s == species) {
+            Some(i) => {
+                self.energy[i] = energy;
+                return Ok(());
+            }
+            None => return Err(format!("No species {} in this burrow", species)),
+        }
+    }
+
+    /// The state where everyone is home and the hallway is empty.
+    pub fn goal(&self) -> BurrowState {
+        let mut spaces = vec![0; self.hallway_len];
+        for room_index in 0..self.num_rooms() {
+            spaces.extend(vec![room_index as u8 + 1; self.room_depth]);
+        }
+        return BurrowState { spaces };
+    }
+
+    /// Which hallway or room space a space index refers to.
+    fn location(&self, space: usize) -> Location {
+        if space < self.hallway_len {
+            return Location::Hallway(space);
+        }
+        let room_space = space - self.hallway_len;
+        return Location::Room {
+            room: room_space / self.room_depth,
+            depth: room_space % self.room_depth,
+        };
+    }
+
+    fn room_space(&self, room_index: usize, depth: usize) -> usize {
+        return self.hallway_len + room_index * self.room_depth + depth;
+    }
+
+    fn room<'a>(&self, state: &'a BurrowState, room_index: usize) -> &'a [u8] {
+        let start = self.room_space(room_index, 0);
+        return &state.spaces[start..start + self.room_depth];
+    }
+
+    fn is_above_room(&self, x: usize) -> bool {
+        return self.room_x.contains(&x);
+    }
+
+    /// A room can be entered when it only holds amphipods that live there.
+    fn is_room_enterable(&self, state: &BurrowState, room_index: usize) -> bool {
+        return self
+            .room(state, room_index)
+            .iter()
+            .all(|&space| space == 0 || space as usize - 1 == room_index);
+    }
+
+    /// Every hallway space after start_x up to and including target_x is empty.
+    fn is_hallway_clear(&self, state: &BurrowState, start_x: usize, target_x: usize) -> bool {
+        let slice = match start_x.cmp(&target_x) {
+            Ordering::Equal => {
+                return true;
+            }
+            Ordering::Less => &state.spaces[(start_x + 1)..=target_x],
+            Ordering::Greater => &state.spaces[target_x..start_x],
+        };
+        return slice.iter().all(|&space| space == 0);
+    }
+
+    // Deepest empty space in a room, for an amphipod moving in.
+    fn free_depth(&self, state: &BurrowState, room_index: usize) -> Option<usize> {
+        return self.room(state, room_index).iter().rposition(|&s| s == 0);
+    }
+
+    // Moves the amphipod at `from` to `to`, working out what it cost.
+    fn moved(&self, state: &BurrowState, from: usize, to: usize, steps: usize) -> Transition {
+        let species = state.spaces[from] as usize - 1;
+        let mut next = state.clone();
+        next.spaces.swap(from, to);
+        return Transition {
+            state: next,
+            energy: steps * self.energy[species],
+            from,
+            to,
+        };
+    }
+
+    /// Every state reachable in one move.
+    fn transitions(&self, state: &BurrowState) -> Vec<Transition> {
+        let mut transitions = Vec::new();
+
+        // Out of a room, either straight home or into the hallway.
+        for room_index in 0..self.num_rooms() {
+            if self.is_room_enterable(state, room_index) {
+                continue;
+            }
+            let room = self.room(state, room_index);
+            let depth = room.iter().position(|&s| s != 0).unwrap();
+            let from = self.room_space(room_index, depth);
+            let target_room = room[depth] as usize - 1;
+            let current_x = self.room_x[room_index];
+
+            // Rooms can sit right next to each other here, so going home directly can be cheaper
+            // than any stop in the hallway.
+            if self.is_room_enterable(state, target_room) {
+                let target_x = self.room_x[target_room];
+                if self.is_hallway_clear(state, current_x, target_x) {
+                    let target_depth = self.free_depth(state, target_room).unwrap();
+                    let steps = depth + 1 + abs_diff(current_x, target_x) + target_depth + 1;
+                    let to = self.room_space(target_room, target_depth);
+                    transitions.push(self.moved(state, from, to, steps));
+                }
+            }
+
+            let left = (0..current_x).rev().take_while(|&x| state.spaces[x] == 0);
+            let right = ((current_x + 1)..self.hallway_len).take_while(|&x| state.spaces[x] == 0);
+            for target_x in left.chain(right) {
+                if self.is_above_room(target_x) {
+                    continue;
+                }
+                let steps = depth + 1 + abs_diff(current_x, target_x);
+                transitions.push(self.moved(state, from, target_x, steps));
+            }
+        }
+
+        // Out of the hallway, only ever into the right room.
+        for current_x in 0..self.hallway_len {
+            if state.spaces[current_x] == 0 {
+                continue;
+            }
+            let target_room = state.spaces[current_x] as usize - 1;
+            if !self.is_room_enterable(state, target_room) {
+                continue;
+            }
+            let target_x = self.room_x[target_room];
+            if !self.is_hallway_clear(state, current_x, target_x) {
+                continue;
+            }
+            let target_depth = self.free_depth(state, target_room).unwrap();
+            let steps = target_depth + 1 + abs_diff(current_x, target_x);
+            let to = self.room_space(target_room, target_depth);
+            transitions.push(self.moved(state, current_x, to, steps));
+        }
+
+        return transitions;
+    }
+
+    /// Draw a state the same way the puzzle does.
+    ///
+    /// ```
+    /// let diagram = "#########\n#.......#\n###B#A###\n  #A#B#\n  #####";
+    /// let (burrow, state) = day23::parse_burrow(diagram).unwrap();
+    /// assert_eq!(burrow.render(&state), diagram);
+    /// ```
+    pub fn render(&self, state: &BurrowState) -> String {
+        let space_to_char = |space: u8| -> char {
+            if space == 0 {
+                return '.';
+            }
+            return self.species[space as usize - 1];
+        };
+
+        let mut lines: Vec<String> = Vec::new();
+        lines.push("#".repeat(self.hallway_len + 2));
+        let hallway: String = state.spaces[..self.hallway_len]
+            .iter()
+            .map(|&s| space_to_char(s))
+            .collect();
+        lines.push(format!("#{}#", hallway));
+
+        let first = *self.room_x.first().unwrap_or(&0);
+        let last = *self.room_x.last().unwrap_or(&0);
+        for depth in 0..self.room_depth {
+            // The top row of rooms runs wall to wall, lower rows only wrap the rooms.
+            let (start, end) = if depth == 0 {
+                (0, self.hallway_len + 2)
+            } else {
+                (first, last + 3)
+            };
+            let mut row: Vec<char> = vec![' '; end];
+            for space in row.iter_mut().skip(start) {
+                *space = '#';
+            }
+            for (room_index, x) in self.room_x.iter().enumerate() {
+                row[x + 1] = space_to_char(state.spaces[self.room_space(room_index, depth)]);
+            }
+            lines.push(row.into_iter().collect::<String>());
+        }
+        lines.push(format!(
+            "{}{}",
+            " ".repeat(first),
+            "#".repeat(last - first + 3)
+        ));
+        return lines.join("\n");
+    }
+}
+
+impl Burrow {
+    /// Every step of a solution, as diagrams with the energy spent so far.
+    ///
+    /// ```
+    /// let input = "#######\n#.....#\n###B#A#\n  #####";
+    /// let (burrow, state) = day23::parse_burrow(input).unwrap();
+    /// let solution = day23::solve_burrow_with_moves(&burrow, &state).unwrap();
+    /// let frames = burrow.replay(&solution);
+    /// assert_eq!(frames.len(), 4);
+    /// assert_eq!(frames[0], format!("Start, energy 0\n{}", input));
+    /// assert_eq!(frames[1], "A moves from room 1 depth 0 to hallway 1 for 4 energy, energy 4\n#######\n#.A...#\n###B#.#\n  #####");
+    /// assert_eq!(frames[2], "B moves from room 0 depth 0 to room 1 depth 0 for 40 energy, energy 44\n#######\n#.A...#\n###.#B#\n  #####");
+    /// assert_eq!(frames[3], "A moves from hallway 1 to room 0 depth 0 for 2 energy, energy 46\n#######\n#.....#\n###A#B#\n  #####");
+    /// ```
+    pub fn replay(&self, solution: &BurrowSolution) -> Vec<String> {
+        let mut frames: Vec<String> = Vec::new();
+        let mut total = 0;
+        for (i, state) in solution.states.iter().enumerate() {
+            let heading = if i == 0 {
+                "Start".to_string()
+            } else {
+                let step = &solution.moves[i - 1];
+                total += step.energy;
+                step.to_string()
+            };
+            frames.push(format!(
+                "{}, energy {}\n{}",
+                heading,
+                total,
+                self.render(state)
+            ));
+        }
+        return frames;
+    }
+}
+
+fn abs_diff(a: usize, b: usize) -> usize {
+    if a < b {
+        return b - a;
+    } else {
+        return a - b;
+    }
+}
+
+/// Parse any burrow diagram.
+///
+/// The hallway is the first row with open spaces, and every column with a space below it is a room.
+/// All rooms have to be the same depth, there has to be one species per room, and each species has
+/// to fill exactly one room. Amphipods may already be out in the hallway.
+///
+/// Each species starts out costing ten times the one before it, so a step for the twenty-first
+/// would be more energy than fits in a usize. Diagrams with that many species are an Err.
+///
+/// ```
+/// let input = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#  \n  #########  ";
+/// let (burrow, _) = day23::parse_burrow(input).unwrap();
+/// assert_eq!(burrow.hallway_len(), 11);
+/// assert_eq!(burrow.num_rooms(), 4);
+/// assert_eq!(burrow.room_depth(), 2);
+/// assert_eq!(burrow.energy('D'), Some(1000));
+///
+/// assert!(day23::parse_burrow("#####\n#...#\n###A###\n  #B#\n  ###").is_err());
+///
+/// // Any number of species, up to that limit, in rooms one deep.
+/// let diagram = |n: u8| {
+///     let width = 2 * n as usize + 1;
+///     let rooms: String = (b'A'..b'A' + n).rev().map(|c| format!("{}#", c as char)).collect();
+///     return format!("#{}#\n#{}#\n###{}##", "#".repeat(width), ".".repeat(width), rooms);
+/// };
+/// let (burrow, _) = day23::parse_burrow(&diagram(20)).unwrap();
+/// assert_eq!(burrow.energy('T'), Some(10usize.pow(19)));
+/// assert!(day23::parse_burrow(&diagram(21)).is_err());
+/// ```
+pub fn parse_burrow(input: &str) -> Result<(Burrow, BurrowState), String> {
+    let is_space = |c: char| c == '.' || c.is_ascii_alphabetic();
+    let lines: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+
+    let hallway_row = lines
+        .iter()
+        .position(|l| l.iter().any(|&c| is_space(c)))
+        .ok_or("No hallway in diagram")?;
+    let hallway_line = &lines[hallway_row];
+    let hallway_start = hallway_line.iter().position(|&c| is_space(c)).unwrap();
+    let hallway_len = hallway_line[hallway_start..]
+        .iter()
+        .take_while(|&&c| is_space(c))
+        .count();
+
+    let mut room_columns: Vec<usize> = Vec::new();
+    let mut room_rows: Vec<&Vec<char>> = Vec::new();
+    for line in lines.iter().skip(hallway_row + 1) {
+        let columns: Vec<usize> = (0..line.len()).filter(|&i| is_space(line[i])).collect();
+        if columns.is_empty() {
+            break;
+        }
+        if room_rows.is_empty() {
+            room_columns = columns;
+        } else if columns != room_columns {
+            return Err(format!(
+                "Room row {} doesn't line up with the rooms above",
+                room_rows.len()
+            ));
+        }
+        room_rows.push(line);
+    }
+    if room_columns.is_empty() {
+        return Err("No rooms in diagram".to_string());
+    }
+    for column in room_columns.iter() {
+        if *column < hallway_start || *column >= hallway_start + hallway_len {
+            return Err(format!("Room in column {} isn't under the hallway", column));
+        }
+    }
+
+    let mut letters: Vec<char> = hallway_line[hallway_start..hallway_start + hallway_len]
+        .iter()
+        .chain(
+            room_rows
+                .iter()
+                .flat_map(|r| room_columns.iter().map(move |&c| &r[c])),
+        )
+        .filter(|c| c.is_ascii_alphabetic())
+        .cloned()
+        .collect();
+    letters.sort();
+    let mut species = letters.clone();
+    species.dedup();
+    if species.len() != room_columns.len() {
+        return Err(format!(
+            "Found {} species for {} rooms",
+            species.len(),
+            room_columns.len()
+        ));
+    }
+    for s in species.iter() {
+        let count = letters.iter().filter(|&l| l == s).count();
+        if count != room_rows.len() {
+            return Err(format!(
+                "Species {} has {} amphipods to fill rooms of {}",
+                s,
+                count,
+                room_rows.len()
+            ));
+        }
+    }
+
+    let encode = |c: char| -> u8 {
+        return match species.iter().position(|&s| s == c) {
+            Some(i) => i as u8 + 1,
+            None => 0,
+        };
+    };
+    let mut spaces: Vec<u8> = hallway_line[hallway_start..hallway_start + hallway_len]
+        .iter()
+        .map(|&c| encode(c))
+        .collect();
+    for column in room_columns.iter() {
+        for row in room_rows.iter() {
+            spaces.push(encode(row[*column]));
+        }
+    }
+
+    let mut energy = Vec::new();
+    for (i, s) in species.iter().enumerate() {
+        let cost = 10usize
+            .checked_pow(i as u32)
+            .ok_or_else(|| format!("Species {} costs too much energy per step", s))?;
+        energy.push(cost);
+    }
+    let burrow = Burrow {
+        hallway_len,
+        room_x: room_columns.iter().map(|c| c - hallway_start).collect(),
+        room_depth: room_rows.len(),
+        species,
+        energy,
+    };
+    return Ok((burrow, BurrowState { spaces }));
+}
+
+/// How the search picks which state to look at next.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SearchStrategy {
+    /// Cheapest energy so far first.
+    Dijkstra,
+    /// Cheapest energy so far plus `Burrow::lower_bound` first.
+    AStar,
+}
+
+/// How much work a search did.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SearchStats {
+    /// States taken off the queue and expanded into their transitions.
+    pub states_expanded: usize,
+    /// Largest the queue got.
+    pub peak_frontier: usize,
+    /// States with a known best energy by the end.
+    pub cache_size: usize,
+}
+
+impl Display for SearchStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return write!(
+            f,
+            "expanded {} states, peak frontier {}, cache size {}",
+            self.states_expanded, self.peak_frontier, self.cache_size
+        );
+    }
+}
+
+impl Burrow {
+    /// Least energy every amphipod needs to get home if nothing was ever in its way.
+    ///
+    /// Amphipods that are home with only their own kind below them need nothing. Ones that are
+    /// home but trapping a stranger have to step out, aside, back and in. Everyone else has to
+    /// walk to the space above their room and take at least one step down into it. Since this
+    /// never overestimates, it is safe to use as an A* heuristic.
+    ///
+    /// ```
+    /// let input = "#######\n#.....#\n###B#A#\n  #####";
+    /// let (burrow, state) = day23::parse_burrow(input).unwrap();
+    /// // A walks 1 + 2 + 1 steps, B walks 1 + 2 + 1 steps at 10 each.
+    /// assert_eq!(burrow.lower_bound(&state), 44);
+    /// assert_eq!(burrow.lower_bound(&burrow.goal()), 0);
+    /// ```
+    pub fn lower_bound(&self, state: &BurrowState) -> usize {
+        let mut total = 0;
+        for x in 0..self.hallway_len {
+            if state.spaces[x] == 0 {
+                continue;
+            }
+            let species = state.spaces[x] as usize - 1;
+            let steps = abs_diff(x, self.room_x[species]) + 1;
+            total += steps * self.energy[species];
+        }
+        for room_index in 0..self.num_rooms() {
+            let room = self.room(state, room_index);
+            let current_x = self.room_x[room_index];
+            for (depth, &space) in room.iter().enumerate() {
+                if space == 0 {
+                    continue;
+                }
+                let species = space as usize - 1;
+                let steps = if species != room_index {
+                    depth + 1 + abs_diff(current_x, self.room_x[species]) + 1
+                } else if room[depth..].iter().any(|&s| s as usize != room_index + 1) {
+                    depth + 4
+                } else {
+                    0
+                };
+                total += steps * self.energy[species];
+            }
+        }
+        return total;
+    }
+}
+
+#[derive(PartialEq, Eq)]
+struct Entry {
+    state: BurrowState,
+    energy: usize,
+    priority: usize,
+}
+
+impl PartialOrd<Self> for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return self.priority.cmp(&other.priority).reverse();
+    }
+}
+
+/// Least energy needed to organise the burrow, None if it can't be done.
+///
+/// ```
+/// let input = "#############\n#...........#\n###B#C#B#D###\n  #D#C#B#A#\n  #D#B#A#C#\n  #A#D#C#A#\n  #########";
+/// let (burrow, state) = day23::parse_burrow(input).unwrap();
+/// assert_eq!(day23::solve_burrow(&burrow, &state), Some(44169));
+///
+/// // A short hallway, three rooms, and C being cheap to move.
+/// let input = "###########\n#.........#\n###C#A#B###\n  #A#B#C#\n  #######";
+/// let (mut burrow, state) = day23::parse_burrow(input).unwrap();
+/// burrow.set_energy('C', 1).unwrap();
+/// assert_eq!(day23::solve_burrow(&burrow, &state), Some(52));
+/// ```
+pub fn solve_burrow(burrow: &Burrow, initial: &BurrowState) -> Option<usize> {
+    return solve_burrow_with_moves(burrow, initial).map(|solution| solution.energy);
+}
+
+/// Like `solve_burrow`, but also works out which moves got there.
+///
+/// Searches with A*, which finds the same least energy as Dijkstra while looking at far
+/// fewer states.
+///
+/// ```
+/// let input = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########";
+/// let (burrow, state) = day23::parse_burrow(input).unwrap();
+/// let solution = day23::solve_burrow_with_moves(&burrow, &state).unwrap();
+/// assert_eq!(solution.energy, 12521);
+/// assert_eq!(solution.moves.iter().map(|m| m.energy).sum::<usize>(), 12521);
+/// assert_eq!(solution.states.len(), solution.moves.len() + 1);
+/// assert_eq!(solution.states.last(), Some(&burrow.goal()));
+/// ```
+pub fn solve_burrow_with_moves(burrow: &Burrow, initial: &BurrowState) -> Option<BurrowSolution> {
+    let (solution, _) = solve_burrow_with_stats(burrow, initial, SearchStrategy::AStar);
+    return solution;
+}
+
+/// Solve with the given strategy, and report how much searching it took.
+///
+/// ```
+/// use day23::SearchStrategy;
+/// let input = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########";
+/// let (burrow, state) = day23::parse_burrow(input).unwrap();
+/// let (dijkstra, dijkstra_stats) = day23::solve_burrow_with_stats(&burrow, &state, SearchStrategy::Dijkstra);
+/// let (a_star, a_star_stats) = day23::solve_burrow_with_stats(&burrow, &state, SearchStrategy::AStar);
+/// assert_eq!(dijkstra.unwrap().energy, 12521);
+/// assert_eq!(a_star.unwrap().energy, 12521);
+/// assert!(a_star_stats.states_expanded < dijkstra_stats.states_expanded);
+/// assert!(a_star_stats.cache_size <= dijkstra_stats.cache_size);
+/// ```
+pub fn solve_burrow_with_stats(
+    burrow: &Burrow,
+    initial: &BurrowState,
+    strategy: SearchStrategy,
+) -> (Option<BurrowSolution>, SearchStats) {
+    let priority = |state: &BurrowState, energy: usize| -> usize {
+        return match strategy {
+            SearchStrategy::Dijkstra => energy,
+            SearchStrategy::AStar => energy + burrow.lower_bound(state),
+        };
+    };
+
+    let mut stats = SearchStats::default();
+    let mut q = BinaryHeap::new();
+    q.push(Entry {
+        state: initial.clone(),
+        energy: 0,
+        priority: priority(initial, 0),
+    });
+    let mut best: FxHashMap<BurrowState, usize> = FxHashMap::default();
+    best.insert(initial.clone(), 0);
+    // How we got to each state: the state before it, and the spaces that changed.
+    let mut came_from: FxHashMap<BurrowState, (BurrowState, usize, usize)> = FxHashMap::default();
+    let goal = burrow.goal();
+
+    while let Some(Entry { state, energy, .. }) = q.pop() {
+        if state == goal {
+            stats.cache_size = best.len();
+            let solution = rebuild_solution(burrow, &came_from, &best, state, energy);
+            return (Some(solution), stats);
+        }
+        if energy > best[&state] {
+            // Already found a cheaper way here.
+            continue;
+        }
+        stats.states_expanded += 1;
+        for transition in burrow.transitions(&state) {
+            let next_energy = energy + transition.energy;
+            if next_energy < *best.get(&transition.state).unwrap_or(&usize::MAX) {
+                best.insert(transition.state.clone(), next_energy);
+                came_from.insert(
+                    transition.state.clone(),
+                    (state.clone(), transition.from, transition.to),
+                );
+                q.push(Entry {
+                    priority: priority(&transition.state, next_energy),
+                    state: transition.state,
+                    energy: next_energy,
+                });
+            }
+        }
+        stats.peak_frontier = stats.peak_frontier.max(q.len());
+    }
+
+    stats.cache_size = best.len();
+    return (None, stats);
+}
+
+// Walk back from the goal to the start to recover the moves.
+fn rebuild_solution(
+    burrow: &Burrow,
+    came_from: &FxHashMap<BurrowState, (BurrowState, usize, usize)>,
+    best: &FxHashMap<BurrowState, usize>,
+    goal: BurrowState,
+    energy: usize,
+) -> BurrowSolution {
+    let mut moves: Vec<BurrowMove> = Vec::new();
+    let mut states: Vec<BurrowState> = vec![goal];
+    while let Some((previous, from, to)) = came_from.get(states.last().unwrap()) {
+        let after = states.last().unwrap();
+        let species = burrow.species[after.spaces[*to] as usize - 1];
+        moves.push(BurrowMove {
+            species,
+            from: burrow.location(*from),
+            to: burrow.location(*to),
+            energy: best[after] - best[previous],
+        });
+        states.push(previous.clone());
+    }
+    moves.reverse();
+    states.reverse();
+    return BurrowSolution {
+        energy,
+        moves,
+        states,
+    };
+}
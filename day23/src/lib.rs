@@ -3,12 +3,18 @@
 // Now one problem I ran into was the initial implementation incorrectly handled the above room check.
 // In that it allowed underflow. Wrapping fixed this
 pub use filelib::load;
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::BinaryHeap;
 use std::fmt::{Display, Formatter};
 
 use rustc_hash::FxHashMap;
 
+mod burrow;
+pub use crate::burrow::{
+    parse_burrow, solve_burrow, solve_burrow_with_moves, solve_burrow_with_stats, Burrow,
+    BurrowMove, BurrowSolution, BurrowState, Location, SearchStats, SearchStrategy,
+};
+
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Amphipod {
@@ -434,43 +440,34 @@ pub fn puzzle_b(input: &Vec<Amphipod>) -> usize {
     return solve_both_parts(initial_state);
 }
 
-#[derive(PartialEq, Eq)]
-struct Entry {
-    encoded_state: u64,
-    f_score: usize,
-}
-
-impl PartialOrd<Self> for Entry {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        return Some(self.cmp(other));
-    }
-}
-
-impl Ord for Entry {
-    fn cmp(&self, other: &Self) -> Ordering {
-        return self.f_score.cmp(&other.f_score).reverse();
-    }
+/// Add the two folded up rows from part b to a puzzle diagram.
+///
+/// ```
+/// let input = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#  \n  #########  ";
+/// let (burrow, state) = day23::parse_burrow(&day23::unfold_diagram(input)).unwrap();
+/// assert_eq!(burrow.room_depth(), 4);
+/// assert_eq!(day23::solve_burrow(&burrow, &state), Some(44169));
+/// ```
+pub fn unfold_diagram(input: &str) -> String {
+    let mut lines: Vec<&str> = input.lines().collect();
+    lines.insert(3, "  #D#C#B#A#");
+    lines.insert(4, "  #D#B#A#C#");
+    return lines.join("\n");
 }
 
 fn solve_both_parts<const R: usize>(initial_state: State<R>) -> usize {
     // Basically: A* search algorithm.
 
     let mut q = BinaryHeap::new();
-    q.push(Entry {
-        encoded_state: initial_state.encode(),
-        f_score: 0,
-    });
+    // Lowest f score first, as (f score, encoded state).
+    q.push(Reverse((0, initial_state.encode())));
 
     let mut g_score: FxHashMap<u64, usize> = FxHashMap::default();
     g_score.insert(initial_state.encode(), 0);
 
     let encoded_goal_state = State::<R>::goal().encode();
 
-    while let Some(Entry {
-        encoded_state,
-        f_score,
-    }) = q.pop()
-    {
+    while let Some(Reverse((f_score, encoded_state))) = q.pop() {
         if encoded_state == encoded_goal_state {
             return f_score;
         }
@@ -483,10 +480,10 @@ fn solve_both_parts<const R: usize>(initial_state: State<R>) -> usize {
             let tentative_g_score = current_g_score + transition_cost;
             if tentative_g_score < *g_score.get(&encoded_next_state).unwrap_or(&usize::MAX) {
                 g_score.insert(encoded_next_state, tentative_g_score);
-                q.push(Entry {
-                    encoded_state: encoded_next_state,
-                    f_score: tentative_g_score + next_state.h_score(),
-                });
+                q.push(Reverse((
+                    tentative_g_score + next_state.h_score(),
+                    encoded_next_state,
+                )));
             }
         }
     }
@@ -2,10 +2,67 @@ use day23::load;
 use day23::parse_amphipod;
 use day23::puzzle_a;
 use day23::puzzle_b;
+use day23::{parse_burrow, solve_burrow_with_moves, unfold_diagram};
+use day23::{solve_burrow_with_stats, SearchStrategy};
+use std::env;
+use std::thread::sleep;
+use std::time::Duration;
+
+// Print every step of the best solution, or redraw them in place with --animate.
+fn replay(diagram: &str, animate: bool) {
+    let (burrow, state) = parse_burrow(diagram).unwrap();
+    let solution = solve_burrow_with_moves(&burrow, &state).expect("No solution");
+    for frame in burrow.replay(&solution) {
+        if animate {
+            // Clear the screen and go back to the top left.
+            print!("\x1b[2J\x1b[H");
+            println!("{}", frame);
+            sleep(Duration::from_millis(500));
+        } else {
+            println!("{}\n", frame);
+        }
+    }
+}
+
+// Compare how hard Dijkstra and A* have to work on a diagram.
+fn stats(diagram: &str) {
+    let (burrow, state) = parse_burrow(diagram).unwrap();
+    for strategy in [SearchStrategy::Dijkstra, SearchStrategy::AStar] {
+        let (solution, stats) = solve_burrow_with_stats(&burrow, &state, strategy);
+        let energy = match solution {
+            Some(s) => s.energy.to_string(),
+            None => "none".to_string(),
+        };
+        println!(
+            "R = {}, {:?}: energy {}, {}",
+            burrow.room_depth(),
+            strategy,
+            energy,
+            stats
+        );
+    }
+}
 
 fn main() {
     let filename = "input";
     let all_lines = load(filename);
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--stats") {
+        // Any files after --stats are extra diagrams to compare, eg with deeper rooms.
+        stats(&all_lines);
+        stats(&unfold_diagram(&all_lines));
+        for extra in args.iter().skip(i + 1) {
+            stats(&load(extra));
+        }
+        return;
+    }
+    let animate = args.iter().any(|a| a == "--animate");
+    if animate || args.iter().any(|a| a == "--replay") {
+        replay(&all_lines, animate);
+        replay(&unfold_diagram(&all_lines), animate);
+        return;
+    }
+
     let amphipod = parse_amphipod(&all_lines);
 
     let value_a = puzzle_a(&amphipod);
@@ -104,6 +104,58 @@ pub fn parse_line_to_linecoords(line: &str) -> (i32, i32, i32, i32) {
     return (vec_flat[0], vec_flat[1], vec_flat[2], vec_flat[3]);
 }
 
+/// A small seeded random number generator, so randomised tests and benchmarks see the same
+/// numbers every run. Only built with the "testing" feature, so its own tests need
+/// `cargo test --features testing`, which is what `make test` runs.
+///
+/// ```
+/// let (mut rng, mut again) = (filelib::TestRng::new(7), filelib::TestRng::new(7));
+/// for _ in 0..100 {
+///     let roll = rng.below(6);
+///     assert!(roll < 6);
+///     assert_eq!(roll, again.below(6));
+/// }
+/// assert!((0..100).all(|_| (-3..=3).contains(&rng.between(-3, 3))));
+/// let mut values = vec![1, 2, 3, 4, 5];
+/// rng.shuffle(&mut values);
+/// values.sort();
+/// assert_eq!(values, vec![1, 2, 3, 4, 5]);
+/// ```
+#[cfg(feature = "testing")]
+#[derive(Debug, Clone)]
+pub struct TestRng {
+    state: u64,
+}
+
+#[cfg(feature = "testing")]
+impl TestRng {
+    pub fn new(seed: u64) -> Self {
+        return TestRng { state: seed };
+    }
+
+    /// A number from 0 up to but not including `n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        // Knuth's MMIX LCG. Its low bits are poor, so only the high ones are used.
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        return (self.state >> 33) % n;
+    }
+
+    /// A number from `low` to `high`, both included.
+    pub fn between(&mut self, low: i64, high: i64) -> i64 {
+        return low + self.below((high - low + 1) as u64) as i64;
+    }
+
+    /// Put `values` in a random order.
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            values.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +182,26 @@ mod tests {
     fn test_parse_line_to_coords() {
         assert_eq!(parse_line_to_linecoords("6,4 -> 2,0"), (6, 4, 2, 0));
     }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_rng_is_seeded() {
+        let mut rng = TestRng::new(11);
+        let mut again = TestRng::new(11);
+        let mut other = TestRng::new(12);
+        let rolls: Vec<u64> = (0..1000).map(|_| rng.below(10)).collect();
+        assert_eq!(
+            rolls,
+            (0..1000).map(|_| again.below(10)).collect::<Vec<u64>>()
+        );
+        assert_ne!(
+            rolls,
+            (0..1000).map(|_| other.below(10)).collect::<Vec<u64>>()
+        );
+        // Every value turns up, and nothing out of range does.
+        for value in 0..10 {
+            assert!(rolls.contains(&value));
+        }
+        assert!((0..1000).all(|_| (-5..=5).contains(&rng.between(-5, 5))));
+    }
 }